@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use azul_movegen::GameState;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+use crate::{
+    parsing::{FromAzulFEN, ToAzulFEN},
+    protocol::parse_move,
+};
+
+/// The number of attempts made per requested opening before giving up on finding more unique positions.
+const ATTEMPTS_PER_OPENING: usize = 10;
+
+/// Generates an opening book by self-playing the given number of random legal moves from fresh deals.
+/// Returns the AzulFEN of each resulting position, with identical positions removed, so the output
+/// can be loaded directly as openings.
+///
+/// The same seed always produces the same book. Fewer than `count` openings may be returned if not
+/// enough unique positions can be found.
+pub fn generate(players: usize, count: usize, plies: usize, seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut seen = HashSet::new();
+    let mut book = Vec::new();
+
+    for _ in 0..count * ATTEMPTS_PER_OPENING {
+        if book.len() >= count {
+            break;
+        }
+
+        let mut gamestate = GameState::new_seeded(players, rng.random());
+        gamestate.setup_next_round();
+        for _ in 0..plies {
+            let Some(choice) = gamestate.get_valid_moves().choose(&mut rng).cloned() else {
+                break;
+            };
+            gamestate
+                .make_move(&choice)
+                .expect("Generated move was illegal");
+            if gamestate.round_over() {
+                gamestate.setup_next_round();
+            }
+            if gamestate.is_game_over() {
+                break;
+            }
+        }
+
+        let fen = gamestate.to_azul_fen();
+        if seen.insert(fen.clone()) {
+            book.push(fen);
+        }
+    }
+    book
+}
+
+/// Attempting to load an openings file with an invalid line will produce this error.
+/// # Properties
+/// * `line`: the line number of the first invalid line, counting from 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseOpeningError {
+    pub line: usize,
+}
+
+/// Loads the openings from the contents of an openings file, with one opening per line.
+/// Blank lines are ignored.
+///
+/// Each opening is either an AzulFEN position, or a sequence of moves in the six-digit
+/// [move format](crate::protocol), separated by whitespace. Move sequences are played from a fresh
+/// deal for the given number of players, created with the given seed, so they are only
+/// reproducible when the seed is fixed.
+pub fn load(
+    contents: &str,
+    players: usize,
+    seed: u64,
+) -> Result<Vec<GameState>, ParseOpeningError> {
+    let mut openings = Vec::new();
+    for (idx, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = ParseOpeningError { line: idx + 1 };
+
+        // Only an AzulFEN has sections separated by pipes
+        if line.contains('|') {
+            openings.push(GameState::from_azul_fen(line).or(Err(error))?);
+            continue;
+        }
+        let moves = line
+            .split_whitespace()
+            .map(parse_move)
+            .collect::<Result<Vec<_>, _>>()
+            .or(Err(error))?;
+        let mut gamestate = GameState::new_seeded(players, seed);
+        gamestate.setup_next_round();
+        gamestate.play_moves(&moves).or(Err(error))?;
+        openings.push(gamestate);
+    }
+    Ok(openings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::FromAzulFEN;
+
+    #[test]
+    fn generated_openings_parse_back() {
+        let book = generate(2, 5, 2, 21);
+        assert_eq!(book.len(), 5);
+        for fen in book.iter() {
+            let opening = GameState::from_azul_fen(fen).unwrap();
+            assert_eq!(opening.player_count(), 2);
+            assert_eq!(*opening.move_count(), 2);
+        }
+        assert_eq!(generate(2, 5, 2, 21), book);
+    }
+
+    #[test]
+    fn loads_fens_and_move_sequences_together() {
+        let mut expected = GameState::new_seeded(2, 9);
+        expected.setup_next_round();
+        let fen = expected.to_azul_fen();
+        let moves: Vec<_> = (0..2)
+            .map(|_| {
+                let choice = expected.get_valid_moves()[0].clone();
+                expected.make_move(&choice).unwrap();
+                choice.to_string()
+            })
+            .collect();
+
+        let contents = format!("{}\n\n{}\n", fen.trim_end(), moves.join(" "));
+        let openings = load(&contents, 2, 9).unwrap();
+        assert_eq!(openings.len(), 2);
+        assert_eq!(openings[0].to_azul_fen(), fen);
+        assert_eq!(openings[1].to_azul_fen(), expected.to_azul_fen());
+
+        assert_eq!(
+            load(&format!("{}\nnot a move", fen.trim_end()), 2, 9).map(|o| o.len()),
+            Err(ParseOpeningError { line: 2 })
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use azul_movegen::GameState;
+
+use crate::{
+    format::ProtocolFormat,
+    parsing::ToFairAzulFEN,
+    protocol::{EngineConfig, parse_move},
+    runner::{Engine, EngineError, MoveTime},
+};
+
+/// An engine running as a subprocess, communicating over its standard input and output using UAI.
+/// See the [UAI protocol specification](crate::protocol) for details on the commands used.
+pub struct UaiEngine {
+    name: String,
+    limit_mem: Option<u64>,
+    limit_threads: Option<u32>,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl UaiEngine {
+    /// Starts the engine described by the given config, without performing the handshake.
+    pub fn start(config: &EngineConfig) -> io::Result<Self> {
+        let mut command = Command::new(&config.path);
+        if let Some(args) = &config.args {
+            command.args(args.split_whitespace());
+        }
+        if let Some(dir) = &config.dir {
+            command.current_dir(dir);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("Missing engine stdin");
+        let stdout = child.stdout.take().expect("Missing engine stdout");
+
+        // Lines are read on their own thread so that waiting for a reply can time out
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(UaiEngine {
+            name: config.display_name(),
+            limit_mem: config.limit_mem,
+            limit_threads: config.limit_threads,
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    /// Confirms the engine is alive and ready to play, waiting at most the given time for each reply.
+    /// Any configured resource limits are sent as options once the engine has identified itself,
+    /// so that it can limit itself before reporting that it is ready.
+    pub fn handshake(&mut self, timeout: Duration) -> Result<(), EngineError> {
+        self.send("uai")?;
+        self.wait_for(|line| line == "uaiok", timeout)?;
+        if let Some(limit_mem) = self.limit_mem {
+            self.send(&format!("setoption name Memory value {}", limit_mem))?;
+        }
+        if let Some(limit_threads) = self.limit_threads {
+            self.send(&format!("setoption name Threads value {}", limit_threads))?;
+        }
+        self.send("isready")?;
+        self.wait_for(|line| line == "readyok", timeout)?;
+        Ok(())
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{}", command).or(Err(EngineError::Crash))?;
+        self.stdin.flush().or(Err(EngineError::Crash))
+    }
+
+    /// Waits for a line matching the given predicate, ignoring any other lines.
+    /// Timeouts too long to be represented as a deadline, such as the unlimited time given to
+    /// engines without a time control, wait indefinitely.
+    fn wait_for(
+        &mut self,
+        predicate: impl Fn(&str) -> bool,
+        timeout: Duration,
+    ) -> Result<String, EngineError> {
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            let line = match deadline {
+                Some(deadline) => self
+                    .lines
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None => self.lines.recv().or(Err(RecvTimeoutError::Disconnected)),
+            };
+            match line {
+                Ok(line) if predicate(line.trim()) => return Ok(line.trim().to_string()),
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => return Err(EngineError::Timeout),
+                Err(RecvTimeoutError::Disconnected) => return Err(EngineError::Crash),
+            }
+        }
+    }
+}
+
+impl Engine for UaiEngine {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn go(&mut self, state: &GameState, time: MoveTime) -> Result<String, EngineError> {
+        self.send(&format!(
+            "position fen {}",
+            state.to_fair_azul_fen().trim_end()
+        ))?;
+        match time {
+            MoveTime::Fixed(time) => self.send(&format!("go movetime {}", time.as_millis()))?,
+            MoveTime::Clock {
+                remaining,
+                increment,
+            } => self.send(&format!(
+                "go time {} inc {}",
+                remaining.as_millis(),
+                increment.as_millis()
+            ))?,
+        }
+        self.wait_for(|line| line.starts_with("bestmove"), time.limit())
+    }
+}
+
+impl Drop for UaiEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A human player, who is shown the board and types their moves.
+/// Humans are not held to the time limit, and are asked again if they type an illegal move.
+pub struct HumanPlayer<R: BufRead, W: Write> {
+    name: String,
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> HumanPlayer<R, W> {
+    /// Creates a new human player with the given name, reading moves from the given input, and
+    /// showing the board on the given output.
+    pub fn new(name: String, input: R, output: W) -> Self {
+        HumanPlayer {
+            name,
+            input,
+            output,
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Engine for HumanPlayer<R, W> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn go(&mut self, state: &GameState, _time: MoveTime) -> Result<String, EngineError> {
+        writeln!(self.output, "{}", state.fmt_human()).or(Err(EngineError::Crash))?;
+        loop {
+            write!(self.output, "move: ").or(Err(EngineError::Crash))?;
+            self.output.flush().or(Err(EngineError::Crash))?;
+            let mut input = String::new();
+            match self.input.read_line(&mut input) {
+                Ok(0) | Err(_) => return Err(EngineError::Crash),
+                Ok(_) => {}
+            }
+            let input = input.trim();
+            match parse_move(input) {
+                Ok(choice) if state.is_legal(&choice) => return Ok(format!("bestmove {}", input)),
+                Ok(_) => writeln!(self.output, "Illegal move").or(Err(EngineError::Crash))?,
+                Err(e) => {
+                    writeln!(self.output, "Invalid move: {}", e).or(Err(EngineError::Crash))?
+                }
+            }
+        }
+    }
+
+    fn timed(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use super::*;
+    use crate::{
+        protocol::Protocol,
+        record::Termination,
+        runner::{self, MatchConfig},
+    };
+
+    /// Writes an executable script which replies to the UAI handshake, and always plays `000000`.
+    fn scripted_engine(name: &str) -> EngineConfig {
+        let path = std::env::temp_dir().join(format!("{}-{}.sh", name, std::process::id()));
+        fs::write(
+            &path,
+            "#!/bin/sh\n\
+             while read line; do\n\
+             case \"$line\" in\n\
+             uai) echo uaiok;;\n\
+             isready) echo readyok;;\n\
+             go*) echo 'bestmove 000000';;\n\
+             quit) exit 0;;\n\
+             esac\n\
+             done\n",
+        )
+        .unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        EngineConfig {
+            path: path.to_string_lossy().into_owned(),
+            proto: Protocol::UAI,
+            tc: None,
+            dir: None,
+            args: None,
+            name: Some(name.to_string()),
+            limit_mem: None,
+            limit_threads: None,
+        }
+    }
+
+    /// Plays the first legal move in every position.
+    struct FirstMove;
+
+    impl Engine for FirstMove {
+        fn name(&self) -> &str {
+            "first"
+        }
+
+        fn go(&mut self, state: &GameState, _time: MoveTime) -> Result<String, EngineError> {
+            Ok(format!("bestmove {}", state.get_valid_moves()[0]))
+        }
+    }
+
+    #[test]
+    fn human_plays_a_short_game_against_an_engine() {
+        let mut start = GameState::new_seeded(2, 1);
+        start.setup_next_round();
+
+        // The human also plays the first legal move, after mistyping their first move
+        let mut script = String::from("garbage\n");
+        let mut state = start.clone();
+        let mut moves = Vec::new();
+        for _ in 0..6 {
+            let choice = state.get_valid_moves()[0].clone();
+            if *state.active_player() == 0 {
+                script.push_str(&format!("{}\n", choice));
+            }
+            state.make_move(&choice).unwrap();
+            moves.push(choice);
+        }
+
+        let mut output = Vec::new();
+        let config = MatchConfig {
+            time_controls: vec![None, None],
+            adjudicate_moves: Some(6),
+            resign_score: None,
+        };
+        let record = {
+            let mut human = HumanPlayer::new("human".to_string(), script.as_bytes(), &mut output);
+            runner::play_game(&mut [&mut human, &mut FirstMove], 1, &config)
+        };
+
+        assert_eq!(record.termination, Termination::MoveLimit);
+        assert_eq!(record.players, ["human", "first"]);
+        assert_eq!(record.moves, moves);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Invalid move"));
+        assert!(output.contains("player 0 (active)"));
+    }
+
+    #[test]
+    fn configured_names_head_the_record() {
+        let first = scripted_engine("named-first");
+        let mut second = first.clone();
+        second.name = Some("named-second".to_string());
+        let mut first_engine = UaiEngine::start(&first).unwrap();
+        let mut second_engine = UaiEngine::start(&second).unwrap();
+
+        let config = MatchConfig {
+            time_controls: vec![None, None],
+            adjudicate_moves: Some(0),
+            resign_score: None,
+        };
+        let record = runner::play_game(&mut [&mut first_engine, &mut second_engine], 1, &config);
+        assert_eq!(record.players, ["named-first", "named-second"]);
+        assert!(
+            record
+                .to_text_line()
+                .starts_with("named-first vs named-second: ")
+        );
+
+        drop((first_engine, second_engine));
+        let _ = fs::remove_file(first.path);
+    }
+
+    #[test]
+    fn resource_limits_are_sent_as_options() {
+        let log = std::env::temp_dir().join(format!("limits-{}.log", std::process::id()));
+        let mut config = scripted_engine("limits");
+        config.limit_mem = Some(64);
+        config.limit_threads = Some(2);
+        // Every command the engine receives is logged alongside the usual replies
+        let script = fs::read_to_string(&config.path).unwrap().replace(
+            "while read line; do\n",
+            &format!("while read line; do\necho \"$line\" >> {}\n", log.display()),
+        );
+        fs::write(&config.path, script).unwrap();
+
+        let mut engine = UaiEngine::start(&config).unwrap();
+        engine.handshake(Duration::from_secs(5)).unwrap();
+        drop(engine);
+
+        let received = fs::read_to_string(&log).unwrap();
+        let received: Vec<_> = received.lines().collect();
+        assert_eq!(
+            received[..4],
+            [
+                "uai",
+                "setoption name Memory value 64",
+                "setoption name Threads value 2",
+                "isready"
+            ]
+        );
+
+        let _ = fs::remove_file(config.path);
+        let _ = fs::remove_file(log);
+    }
+
+    #[test]
+    fn unlimited_time_waits_for_reply() {
+        let config = scripted_engine("unlimited");
+        let mut engine = UaiEngine::start(&config).unwrap();
+        engine.handshake(Duration::from_secs(5)).unwrap();
+
+        let mut state = GameState::new_seeded(2, 1);
+        state.setup_next_round();
+        let reply = engine.go(&state, MoveTime::start(None)).unwrap();
+        assert_eq!(reply, "bestmove 000000");
+
+        drop(engine);
+        let _ = fs::remove_file(config.path);
+    }
+}
@@ -1,4 +1,9 @@
-use azul_movegen::{Bag, Board, Bowl, GameState, board::BOARD_DIMENSION};
+use std::{fmt::Display, str::FromStr};
+
+use azul_movegen::{
+    Bag, Board, Bowl, GameState, Tile, TileSource,
+    board::{BOARD_DIMENSION, FLOOR_PENALTIES},
+};
 
 use crate::{parsing::ToAzulFEN, protocol::Protocol};
 
@@ -37,9 +42,26 @@ impl ProtocolFormat for GameState {
         output.push_str(&"-".repeat(20));
         output.push('\n');
 
-        // Bowl printouts
+        // First player token, which decides who starts the next round
+        output.push_str(&match self.first_token_owner() {
+            Some(owner) => format!("first player token: player {}\n", owner),
+            None => String::from("first player token: none\n"),
+        });
+
+        // Bowl printouts, with the centre labelled along with the first player token
         for (i, bowl) in self.bowls().iter().enumerate() {
-            output.push_str(&format!("{}: {} | ", i, bowl.fmt_human()));
+            match TileSource::from_bowl_index(i) {
+                TileSource::Centre => output.push_str(&format!(
+                    "centre: {}{} | ",
+                    bowl.fmt_human(),
+                    if self.first_token_owner().is_none() {
+                        " (first player token)"
+                    } else {
+                        ""
+                    }
+                )),
+                TileSource::Bowl(_) => output.push_str(&format!("{}: {} | ", i, bowl.fmt_human())),
+            }
         }
         output
     }
@@ -55,8 +77,8 @@ impl ProtocolFormat for Board {
         for ((h_idx, hold), row) in self.holds().iter().enumerate().zip(self.placed()) {
             output.push_str(&(h_idx + 1).to_string());
             output.push_str(&"  ".repeat(BOARD_DIMENSION - h_idx));
-            for h in 0..h_idx + 1 {
-                if let Some(h) = hold.get(h).and_then(|x| *x) {
+            for h in hold_cells(hold, h_idx) {
+                if let Some(h) = h {
                     output.push_str(&h.to_string());
                     output.push(' ');
                 } else {
@@ -75,7 +97,20 @@ impl ProtocolFormat for Board {
             output.push('\n');
         }
         output.push_str(&format!("score: {}\n", self.score()));
-        output.push_str(&format!("penalties: {}", self.penalties()));
+
+        // The floor, with a slot for each scored penalty tile and the points it loses below
+        output.push_str("floor:");
+        for slot in 0..FLOOR_PENALTIES.len() {
+            output.push_str(if slot < *self.penalties() {
+                "  x"
+            } else {
+                "  ."
+            });
+        }
+        output.push_str("\n      ");
+        for points in FLOOR_PENALTIES {
+            output.push_str(&format!(" -{}", points));
+        }
         output.push('\n');
         output.push('\n');
         output
@@ -146,6 +181,70 @@ impl ProtocolFormat for Board {
     }
 }
 
+/// Returns the cells to display for the hold at the given row index, exactly filling the row's
+/// capacity. Held tiles are shown first, and any tiles beyond the row's capacity are ignored, so
+/// malformed holds still render as a staircase.
+fn hold_cells(hold: &[Option<Tile>], row_idx: usize) -> impl Iterator<Item = Option<Tile>> + '_ {
+    let capacity = row_idx + 1;
+    hold.iter()
+        .flatten()
+        .take(capacity)
+        .copied()
+        .map(Some)
+        .chain(std::iter::repeat(None))
+        .take(capacity)
+}
+
+/// Extension trait for rendering with Unicode box-drawing characters, for terminals which
+/// support them.
+pub trait UnicodeFormat {
+    fn fmt_unicode(&self) -> String;
+}
+
+impl UnicodeFormat for Board {
+    /// Draws the hold staircase beside the wall, each in its own box.
+    /// Empty wall positions show the tile type which belongs there, dimmed.
+    fn fmt_unicode(&self) -> String {
+        let width = BOARD_DIMENSION * 2 + 1;
+        let border = |left: char, middle: char, right: char| {
+            format!(
+                "{left}{}{middle}{}{right}\n",
+                "─".repeat(width),
+                "─".repeat(width)
+            )
+        };
+
+        let mut output = border('┌', '┬', '┐');
+        for (row_idx, (hold, row)) in self.holds().iter().zip(self.placed()).enumerate() {
+            output.push_str("│ ");
+            output.push_str(&"  ".repeat(BOARD_DIMENSION - row_idx - 1));
+            for h in hold_cells(hold, row_idx) {
+                match h {
+                    Some(h) => output.push_str(&h.to_string()),
+                    None => output.push('.'),
+                }
+                output.push(' ');
+            }
+            output.push_str("│ ");
+            for (col_idx, p) in row.iter().enumerate() {
+                match p {
+                    Some(p) => output.push_str(&p.to_string()),
+                    None => output.push_str(&format!(
+                        "\x1b[2m{}\x1b[0m",
+                        Board::get_tile_type_at_pos(row_idx, col_idx)
+                    )),
+                }
+                output.push(' ');
+            }
+            output.push_str("│\n");
+        }
+        output.push_str(&border('└', '┴', '┘'));
+        output.push_str(&format!("score: {}\n", self.score()));
+        output.push_str(&format!("penalties: {}\n", self.penalties()));
+        output
+    }
+}
+
 impl ProtocolFormat for Bowl {
     fn fmt_human(&self) -> String {
         if self.tiles().is_empty() {
@@ -155,20 +254,20 @@ impl ProtocolFormat for Bowl {
     }
 
     fn fmt_uci_like(&self) -> String {
-        self.fmt_human()
+        self.to_fen()
     }
 }
 
 impl<T> ProtocolFormat for Bag<T>
 where
-    T: ToString,
+    T: Display + FromStr,
 {
     fn fmt_human(&self) -> String {
         "".to_string()
     }
 
     fn fmt_uci_like(&self) -> String {
-        self.items().iter().map(|t| t.to_string()).collect()
+        self.to_fen()
     }
 }
 
@@ -186,3 +285,92 @@ impl std::fmt::Display for Row {
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unicode_board_draws_tiles_in_grid_order() {
+        let mut placed = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        for (col, tile) in placed[0].iter_mut().enumerate() {
+            *tile = Some(Board::get_tile_type_at_pos(0, col));
+        }
+        placed[1][0] = Some(Board::get_tile_type_at_pos(1, 0));
+        let board = Board::builder().placed(placed).build();
+        let output = board.fmt_unicode();
+        let lines: Vec<_> = output.lines().collect();
+
+        for glyph in ['┌', '┬', '┐', '└', '┴', '┘', '│', '─'] {
+            assert!(output.contains(glyph), "missing {}", glyph);
+        }
+        assert!(lines[0].starts_with('┌'));
+        assert!(lines[BOARD_DIMENSION + 1].starts_with('└'));
+        assert!(lines[1].ends_with("│ 0 1 2 3 4 │"));
+        assert!(lines[2].contains("│ 4 \x1b[2m0\x1b[0m \x1b[2m1\x1b[0m"));
+    }
+
+    #[test]
+    fn human_output_labels_the_centre() {
+        let mut state = GameState::new_seeded(2, 24);
+        state.setup_next_round();
+        let output = state.fmt_human();
+        assert!(output.contains("centre: - (first player token) | 1: "));
+        assert_eq!(output.matches("centre:").count(), 1);
+    }
+
+    #[test]
+    fn human_output_names_the_token_owner() {
+        let mut state = GameState::new_seeded(2, 25);
+        state.setup_next_round();
+        assert!(state.fmt_human().contains("first player token: none\n"));
+
+        // Take from a bowl so the centre has tiles, then from the centre as the second player
+        let from_bowl = state.get_valid_moves()[0].clone();
+        state.make_move(&from_bowl).unwrap();
+        let from_centre = state
+            .get_valid_moves()
+            .into_iter()
+            .find(|choice| choice.bowl == 0)
+            .unwrap();
+        state.make_move(&from_centre).unwrap();
+
+        let output = state.fmt_human();
+        assert!(output.contains("first player token: player 1\n"));
+        assert!(!output.contains("(first player token)"));
+    }
+
+    #[test]
+    fn overfull_holds_render_at_row_capacity() {
+        let mut holds = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        holds[0] = [Some(0); BOARD_DIMENSION];
+        holds[1] = [Some(1); BOARD_DIMENSION];
+        let board = Board::builder().holds(holds).build();
+
+        let output = board.fmt_human();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[0], "1          0  | . . . . . ");
+        assert_eq!(lines[1], "2        1 1  | . . . . . ");
+
+        // Every row lines up with the others, however full its hold
+        let bar = lines[0].find('|').unwrap();
+        assert!(
+            lines[..BOARD_DIMENSION]
+                .iter()
+                .all(|line| line.find('|') == Some(bar))
+        );
+    }
+
+    #[test]
+    fn floor_line_fills_a_slot_per_penalty() {
+        let board = Board::builder().penalties(3).build();
+        let output = board.fmt_human();
+        let floor = output
+            .lines()
+            .find(|line| line.starts_with("floor:"))
+            .unwrap();
+        assert_eq!(floor, "floor:  x  x  x  .  .  .  .");
+        assert_eq!(floor.matches('x').count(), 3);
+        assert!(output.contains("       -1 -1 -2 -2 -2 -3 -3\n"));
+    }
+}
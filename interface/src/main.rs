@@ -1,17 +1,48 @@
 // #![allow(dead_code)]
 
+pub mod book;
+pub mod engine;
 pub mod format;
 pub mod parsing;
 pub mod protocol;
+pub mod record;
+pub mod runner;
+pub mod selfplay;
+pub mod tournament;
 
-use std::io;
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    process,
+    time::Duration,
+};
 
 use azul_movegen::GameState;
-use rand::seq::IndexedRandom;
+use rand::{SeedableRng, rngs::StdRng};
 
-use crate::{format::ProtocolFormat, protocol::Protocol};
+use crate::{
+    engine::{HumanPlayer, UaiEngine},
+    format::ProtocolFormat,
+    protocol::{Cli, EngineConfig, OutFormat, Protocol, TournamentStyle},
+    record::GameRecord,
+    runner::{ConsoleProgress, Engine, MatchConfig, ProgressReporter},
+    tournament::{LivePrinter, Results},
+};
+
+/// The time given to engines to complete the handshake when no timeout is configured.
+const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
 
 fn main() {
+    let cli = protocol::full_parse();
+    if let Some(style) = cli.tournament {
+        run_tournament(&cli, style);
+        return;
+    }
+    if !cli.engines.is_empty() {
+        play_single_game(&cli);
+        return;
+    }
+
     let mut gamestate = GameState::new(2);
     gamestate.setup_next_round();
     println!("{}", gamestate.fmt_protocol(Protocol::Human));
@@ -19,6 +50,235 @@ fn main() {
     listen_for_input(gamestate, Protocol::Human);
 }
 
+/// Loads the openings in the `--openings` file, if one is given, for games of the given number
+/// of players. See [`book::load`] for the format, and how the seed is used.
+/// Exits if the file can't be read, or doesn't hold any valid openings.
+fn load_openings(cli: &Cli, players: usize, seed: u64) -> Option<Vec<GameState>> {
+    let path = cli.openings.as_ref()?;
+    let contents = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("Failed to read the openings from {}: {}", path, e);
+        process::exit(1);
+    });
+    match book::load(&contents, players, seed) {
+        Ok(openings) if !openings.is_empty() => Some(openings),
+        Ok(_) => {
+            eprintln!("No openings found in {}", path);
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Invalid opening on line {} of {}", e.line, path);
+            process::exit(1);
+        }
+    }
+}
+
+/// Starts each of the configured engines, in the order they were given.
+/// Humans type their moves on the terminal, while UAI engines are run as subprocesses, which must
+/// complete the handshake before the first game.
+fn start_engines(cli: &Cli) -> Vec<Box<dyn Engine>> {
+    let handshake_timeout = cli
+        .timeout
+        .map_or(DEFAULT_HANDSHAKE_TIMEOUT, Duration::from_millis);
+    let mut engines: Vec<Box<dyn Engine>> = Vec::new();
+    for (config, name) in cli.engines.iter().zip(protocol::engine_names(&cli.engines)) {
+        match config.proto {
+            Protocol::Human => engines.push(Box::new(HumanPlayer::new(
+                name,
+                io::stdin().lock(),
+                io::stdout(),
+            ))),
+            Protocol::UAI => {
+                let config = EngineConfig {
+                    name: Some(name),
+                    ..config.clone()
+                };
+                let mut engine = UaiEngine::start(&config).expect("Failed to start engine");
+                engine
+                    .handshake(handshake_timeout)
+                    .expect("Engine failed the handshake");
+                engines.push(Box::new(engine));
+            }
+        }
+    }
+    engines
+}
+
+/// Returns the settings for every game played under the given command line.
+fn match_config(cli: &Cli) -> MatchConfig {
+    MatchConfig {
+        time_controls: cli.engines.iter().map(|config| config.tc.clone()).collect(),
+        adjudicate_moves: cli.adjudicate_moves,
+        resign_score: cli.resign_score,
+    }
+}
+
+/// Plays a single game between the configured engines, seated in the order they were given, or a
+/// game from each of the `--openings` if given.
+/// With `--swap`, each game is then played again from the same deal with the players swapped, so
+/// that each engine faces the deal from both sides.
+fn play_single_game(cli: &Cli) {
+    let mut engines = start_engines(cli);
+    let mut seats: Vec<&mut dyn Engine> = engines
+        .iter_mut()
+        .map(|e| e.as_mut() as &mut dyn Engine)
+        .collect();
+    let seat_order: Vec<_> = (0..seats.len()).collect();
+    let seed = cli.seed.unwrap_or_else(rand::random);
+    let openings = load_openings(cli, seats.len(), seed).unwrap_or_else(|| {
+        let mut start = GameState::new_seeded(seats.len(), seed);
+        start.setup_next_round();
+        vec![start]
+    });
+
+    let mut starts = Vec::new();
+    for start in openings {
+        let swapped = start.with_swapped_players();
+        starts.push(start);
+        if cli.swap {
+            starts.push(swapped);
+        }
+    }
+    let mut reporter = GameReporter {
+        cli,
+        progress: ConsoleProgress::new(io::stdout()),
+    };
+    runner::play_games(
+        &mut seats,
+        &seat_order,
+        starts,
+        &match_config(cli),
+        &mut reporter,
+    );
+}
+
+/// Plays a tournament of the given style between the configured engines, where each game is
+/// played between a pair of engines, see [`tournament::pairings`].
+/// Each of the `--rounds` rounds plays `--games` games for every pairing, from fresh deals seeded
+/// in turn from `--seed`, or from each of the `--openings` in turn if given. With `--swap`, each
+/// deal is also played with the engines swapped.
+/// No more than `--max-games` games are played in total.
+/// With `--summary`, the crosstable and summary of the results are printed once it's over.
+fn run_tournament(cli: &Cli, style: TournamentStyle) {
+    let mut engines = start_engines(cli);
+    let mut seats: Vec<&mut dyn Engine> = engines
+        .iter_mut()
+        .map(|e| e.as_mut() as &mut dyn Engine)
+        .collect();
+    let config = match_config(cli);
+    let seed = cli.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let max_games = cli.max_games.unwrap_or(usize::MAX);
+    let openings = load_openings(cli, 2, seed);
+    let mut reporter = TournamentReporter {
+        cli,
+        results: Results::from_configs(&cli.engines),
+        pairing: [0, 1],
+        played: 0,
+        live: cli.live_updates().then(|| LivePrinter::new(io::stdout())),
+    };
+
+    let mut deals = 0;
+    'rounds: for _ in 0..cli.rounds.unwrap_or(1) {
+        for (engine, opponent) in tournament::pairings(style, &reporter.results, &mut rng) {
+            for _ in 0..cli.games.unwrap_or(1) {
+                if reporter.played >= max_games {
+                    break 'rounds;
+                }
+                let start = match &openings {
+                    Some(openings) => openings[deals % openings.len()].clone(),
+                    None => {
+                        let mut start = GameState::new_seeded(2, seed.wrapping_add(deals as u64));
+                        start.setup_next_round();
+                        start
+                    }
+                };
+                deals += 1;
+
+                let mut starts = vec![start.clone()];
+                if cli.swap && reporter.played + 1 < max_games {
+                    starts.push(start.with_swapped_players());
+                }
+                reporter.pairing = [engine, opponent];
+                runner::play_games(
+                    &mut seats,
+                    &[engine, opponent],
+                    starts,
+                    &config,
+                    &mut reporter,
+                );
+            }
+        }
+    }
+
+    if cli.summary {
+        println!("{}", reporter.results.crosstable());
+        print!("{}", reporter.results.summary());
+    }
+}
+
+/// Records each finished game of a tournament in its results, reports it to the terminal, and
+/// saves it to the `--out` file if one is given.
+/// With live updates, the running score between the game's engines is printed as well.
+struct TournamentReporter<'a> {
+    cli: &'a Cli,
+    results: Results,
+    pairing: [usize; 2],
+    played: usize,
+    live: Option<LivePrinter<io::Stdout>>,
+}
+
+impl ProgressReporter for TournamentReporter<'_> {
+    fn on_game_complete(&mut self, _done: usize, _total: usize, last: &GameRecord) {
+        self.results.record_game(&self.pairing, last);
+        self.played += 1;
+        if !self.cli.quiet {
+            println!("Game {}: {}", self.played, last.to_text_line());
+        }
+        if let Some(live) = &mut self.live {
+            let [engine, opponent] = self.pairing;
+            // Live updates are only informational, so failing to print them is ignored
+            let _ = live.game_finished(&self.results, engine, opponent);
+        }
+        save_record(self.cli, last);
+    }
+}
+
+/// Reports each finished game of a match to the terminal, along with the match's progress, and
+/// saves it to the `--out` file if one is given.
+struct GameReporter<'a> {
+    cli: &'a Cli,
+    progress: ConsoleProgress<io::Stdout>,
+}
+
+impl ProgressReporter for GameReporter<'_> {
+    fn on_game_complete(&mut self, done: usize, total: usize, last: &GameRecord) {
+        println!("Game over ({:?})", last.termination);
+        println!("Result: {}", last.result_line());
+        self.progress.on_game_complete(done, total, last);
+        save_record(self.cli, last);
+    }
+}
+
+/// Saves the given game to the `--out` file if one is given, reporting any failure to do so.
+fn save_record(cli: &Cli, record: &GameRecord) {
+    if let Some(path) = &cli.out
+        && let Err(e) = append_record(path, cli.out_format, record)
+    {
+        eprintln!("Failed to save the game to {}: {}", path, e);
+    }
+}
+
+/// Appends the given game to the file at the given path in the given format, creating the file
+/// if it doesn't exist yet.
+fn append_record(path: &str, format: OutFormat, record: &GameRecord) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let line = match format {
+        OutFormat::Text => record.to_text_line(),
+        OutFormat::Jsonl => record.to_json_line(),
+    };
+    writeln!(file, "{}", line)
+}
+
 fn listen_for_input(mut gamestate: GameState, protocol: Protocol) {
     loop {
         let mut input = String::new();
@@ -29,7 +289,7 @@ fn listen_for_input(mut gamestate: GameState, protocol: Protocol) {
         let choice = match protocol::parse_move(input) {
             Ok(m) => m,
             Err(e) => {
-                println!("Invalid move: {:?}", e);
+                println!("Invalid move: {}", e);
                 continue;
             }
         };
@@ -51,30 +311,3 @@ fn listen_for_input(mut gamestate: GameState, protocol: Protocol) {
     println!("Game over");
     println!("Winner: player {}", gamestate.get_winner());
 }
-
-fn random_playout(mut gamestate: GameState, protocol: Protocol) {
-    loop {
-        io::stdin()
-            .read_line(&mut String::new())
-            .expect("Failed to read input");
-
-        let moves = gamestate.get_valid_moves();
-        let selection = moves.choose(&mut rand::rng()).cloned().unwrap_or_default();
-        println!("selection: {:?}", selection);
-
-        match gamestate.make_move(&selection) {
-            Err(_) => println!("Illegal move"),
-            Ok(_) => println!("{}", gamestate.fmt_protocol(protocol)),
-        };
-
-        if gamestate.round_over() {
-            gamestate.setup_next_round();
-        }
-
-        if gamestate.is_game_over() {
-            break;
-        }
-    }
-    println!("Game over");
-    println!("Winner: player {}", gamestate.get_winner());
-}
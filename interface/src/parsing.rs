@@ -1,20 +1,74 @@
 use azul_movegen::{
     Bag, Board, Bowl, GameState, Tile,
-    board::{BOARD_DIMENSION, BonusTypes},
+    board::{BOARD_DIMENSION, BonusTypes, TILE_TYPES},
+    fen::ParseFenError,
+    gamestate::GameStateBuilder,
 };
 
 use crate::format::ProtocolFormat;
 
 /// Attempting to parse an invalid AzulFEN or AzulFEN component will produce this error.
-#[derive(Debug)]
-pub struct ParseGameStateError;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseGameStateError {
+    /// The AzulFEN is malformed.
+    Malformed,
+    /// A bonus segment of a board does not have exactly one character per bonus.
+    BadBonusLength {
+        section: BonusSection,
+        got: usize,
+        expected: usize,
+    },
+}
+
+/// The bonus segments of a board's AzulFEN component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BonusSection {
+    Rows,
+    Columns,
+    Colors,
+}
+
+impl From<ParseFenError> for ParseGameStateError {
+    fn from(_: ParseFenError) -> Self {
+        ParseGameStateError::Malformed
+    }
+}
 
 pub trait FromAzulFEN: Sized {
     fn from_azul_fen(fen: &str) -> Result<Self, ParseGameStateError>;
+
+    /// Checks whether the given AzulFEN can be parsed, discarding the parsed result.
+    /// Never panics, regardless of the input, so it is suitable as a fuzzing target.
+    fn validate_azul_fen(fen: &str) -> Result<(), ParseGameStateError> {
+        Self::from_azul_fen(fen).map(|_| ())
+    }
+}
+
+/// Extension trait for encoding a gamestate along with a seed for its future shuffles.
+pub trait ToSeededAzulFEN {
+    fn to_azul_fen_with_seed(&self, seed: u64) -> String;
+}
+
+/// Extension trait for encoding only the public information of a gamestate, for players who
+/// must not see the order of the bag.
+pub trait ToFairAzulFEN {
+    fn to_fair_azul_fen(&self) -> String;
 }
 
 pub trait ToAzulFEN {
     fn to_azul_fen(&self) -> String;
+
+    /// Returns a 32-bit FNV-1a checksum of the AzulFEN encoding, ignoring trailing whitespace.
+    /// Checksums are stable across runs, so they can compactly tag positions in logs, and positions
+    /// with different checksums are certain to differ.
+    fn checksum(&self) -> u32 {
+        self.to_azul_fen()
+            .trim_end()
+            .bytes()
+            .fold(0x811c9dc5, |hash, byte| {
+                (hash ^ byte as u32).wrapping_mul(0x01000193)
+            })
+    }
 }
 
 impl FromAzulFEN for Bowl {
@@ -22,16 +76,7 @@ impl FromAzulFEN for Bowl {
     /// It is important to note that the bowl component is not an entire FEN.
     /// See the [AzulFEN protocol specification](crate::protocol) for details on the format.
     fn from_azul_fen(bowl_fen: &str) -> Result<Self, ParseGameStateError> {
-        if bowl_fen.chars().nth(0).ok_or(ParseGameStateError)? == '-' {
-            Ok(Bowl::default())
-        } else {
-            Ok(Bowl::from_tiles(
-                bowl_fen
-                    .chars()
-                    .map(|c| c.to_string().parse::<Tile>().or(Err(ParseGameStateError)))
-                    .collect::<Result<Vec<_>, ParseGameStateError>>()?,
-            ))
-        }
+        Ok(Bowl::from_fen(bowl_fen)?)
     }
 }
 
@@ -54,162 +99,558 @@ impl FromAzulFEN for Board {
             ] => {
                 // Placed
                 let mut placed = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
-                let mut y = 0;
-                let mut x = 0;
-                for p in placed_parts.chars() {
-                    if let Ok(step) = p.to_string().parse::<usize>() {
-                        x += step;
-                    } else if p == '-' {
-                        placed[y][x] = Some(Board::get_tile_type_at_pos(y, x));
-                        x += 1;
+                let rows: Vec<_> = placed_parts.split('/').collect();
+                if rows.len() != BOARD_DIMENSION {
+                    return Err(ParseGameStateError::Malformed);
+                }
+                for (y, row) in rows.into_iter().enumerate() {
+                    let mut x = 0;
+                    for p in row.chars() {
+                        if let Some(step) = p.to_digit(10) {
+                            x += step as usize;
+                        } else if p == '-' {
+                            *placed[y].get_mut(x).ok_or(ParseGameStateError::Malformed)? =
+                                Some(Board::get_tile_type_at_pos(y, x));
+                            x += 1;
+                        } else {
+                            return Err(ParseGameStateError::Malformed);
+                        }
                     }
-                    if x >= BOARD_DIMENSION {
-                        y += 1;
-                        x = 0;
+                    // Each row must account for every position exactly
+                    if x != BOARD_DIMENSION {
+                        return Err(ParseGameStateError::Malformed);
                     }
                 }
+                validate_placed(&placed)?;
                 builder = builder.placed(placed);
 
                 // Held
                 let mut holds = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
                 for (i, h) in held.chars().collect::<Vec<_>>().chunks(2).enumerate() {
-                    let tile_type = h[0]
+                    let [tile_type, tile_count] = h else {
+                        return Err(ParseGameStateError::Malformed);
+                    };
+                    let tile_type = tile_type
                         .to_string()
                         .parse::<Tile>()
-                        .or(Err(ParseGameStateError))?;
-                    let tile_count = h[1]
+                        .or(Err(ParseGameStateError::Malformed))?;
+                    let tile_count = tile_count
                         .to_string()
-                        .parse::<Tile>()
-                        .or(Err(ParseGameStateError))?;
+                        .parse::<usize>()
+                        .or(Err(ParseGameStateError::Malformed))?;
+                    // Each row can only hold as many tiles as its capacity, of a real tile type
+                    let row = holds.get_mut(i).ok_or(ParseGameStateError::Malformed)?;
+                    if tile_count > i + 1 || tile_type >= TILE_TYPES {
+                        return Err(ParseGameStateError::Malformed);
+                    }
                     if tile_count == 0 {
                         continue;
                     }
-                    for n in 0..tile_count {
-                        holds[i][n] = Some(tile_type);
+                    for hold in row.iter_mut().take(tile_count) {
+                        *hold = Some(tile_type);
                     }
                 }
                 builder = builder.holds(holds);
 
                 // Bonuses
                 builder = builder.bonuses(BonusTypes {
-                    rows: bonus_rows
-                        .chars()
-                        .map(|c| c == '1')
-                        .collect::<Vec<_>>()
-                        .try_into()
-                        .or(Err(ParseGameStateError))?,
-                    columns: bonus_cols
-                        .chars()
-                        .map(|c| c == '1')
-                        .collect::<Vec<_>>()
-                        .try_into()
-                        .or(Err(ParseGameStateError))?,
-                    tile_types: bonus_tile_types
-                        .chars()
-                        .map(|c| c == '1')
-                        .collect::<Vec<_>>()
-                        .try_into()
-                        .or(Err(ParseGameStateError))?,
+                    rows: parse_bonuses(bonus_rows, BonusSection::Rows)?,
+                    columns: parse_bonuses(bonus_cols, BonusSection::Columns)?,
+                    tile_types: parse_bonuses(bonus_tile_types, BonusSection::Colors)?,
                 });
 
                 // Score and penalties
-                builder = builder.score(score.parse().or(Err(ParseGameStateError))?);
-                builder = builder.penalties(penalties.parse().or(Err(ParseGameStateError))?);
+                builder = builder.score(score.parse().or(Err(ParseGameStateError::Malformed))?);
+                builder =
+                    builder.penalties(penalties.parse().or(Err(ParseGameStateError::Malformed))?);
             }
-            _ => return Err(ParseGameStateError),
+            _ => return Err(ParseGameStateError::Malformed),
         };
         Ok(builder.build())
     }
 }
 
+/// Parses a bonus segment of a board's AzulFEN component, with a character for each bonus which is
+/// `1` if the bonus has been collected.
+/// Will error naming the segment if it does not have exactly one character per bonus.
+fn parse_bonuses<const N: usize>(
+    segment: &str,
+    section: BonusSection,
+) -> Result<[bool; N], ParseGameStateError> {
+    let bonuses: Vec<_> = segment.chars().map(|c| c == '1').collect();
+    let got = bonuses.len();
+    bonuses
+        .try_into()
+        .or(Err(ParseGameStateError::BadBonusLength {
+            section,
+            got,
+            expected: N,
+        }))
+}
+
+/// Confirms that every placed tile matches the tile type of its position on the fixed wall.
+///
+/// Placed tiles are currently encoded without a tile type and take the type of their position,
+/// so a board parsed from an AzulFEN is always consistent. This check guards any encoding which
+/// states the tile type explicitly.
+fn validate_placed(
+    placed: &[[Option<Tile>; BOARD_DIMENSION]; BOARD_DIMENSION],
+) -> Result<(), ParseGameStateError> {
+    for (y, row) in placed.iter().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            if tile.is_some_and(|t| t != Board::get_tile_type_at_pos(y, x)) {
+                return Err(ParseGameStateError::Malformed);
+            }
+        }
+    }
+    Ok(())
+}
+
 impl FromAzulFEN for GameState {
     /// Parses the given AzulFEN into a gamestate.
     /// Will error if the given AzulFEN is invalid.
     /// See the [AzulFEN protocol specification](crate::protocol) for details on the format.
     fn from_azul_fen(azul_fen: &str) -> Result<Self, ParseGameStateError> {
-        let mut sections = azul_fen.split("| ");
-
-        let board_fens = sections.next().ok_or(ParseGameStateError)?.trim();
-        let mut board_fens: Vec<_> = board_fens.split(";").map(|f| f.trim()).collect();
-        // Last FEN will always be empty since we split at ";" and each board ends with one
-        board_fens.pop();
-        let board_fens = board_fens;
-        let boards = board_fens
-            .into_iter()
-            .map(Board::from_azul_fen)
-            .collect::<Result<Vec<_>, ParseGameStateError>>()?;
-
-        let bowl_fens = sections.next().ok_or(ParseGameStateError)?;
-        let bowls = bowl_fens
-            .trim()
-            .split_ascii_whitespace()
-            .map(Bowl::from_azul_fen)
-            .collect::<Result<Vec<_>, ParseGameStateError>>()?;
-
-        let bag_fen = sections.next().ok_or(ParseGameStateError)?;
-        let items = bag_fen
-            .chars()
-            .map(|c| c.to_string().parse::<Tile>().or(Err(ParseGameStateError)))
-            .collect::<Result<Vec<_>, ParseGameStateError>>()?;
-        let bag = Bag::new(items);
-
-        let active_player_and_first_token = sections.next().ok_or(ParseGameStateError)?;
-        let (active_player, first_token_owner) = match active_player_and_first_token
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .as_slice()
-        {
-            [active_player, first_token_owner] => (
-                active_player
+        parse_game_state(azul_fen)?
+            .try_build()
+            .or(Err(ParseGameStateError::Malformed))
+    }
+
+    /// Checks whether the given AzulFEN describes a valid gamestate, without building it.
+    /// Never panics, regardless of the input, so it is suitable as a fuzzing target.
+    fn validate_azul_fen(azul_fen: &str) -> Result<(), ParseGameStateError> {
+        parse_game_state(azul_fen)?
+            .validate()
+            .or(Err(ParseGameStateError::Malformed))
+    }
+}
+
+/// Parses each component of the given AzulFEN into a builder for the gamestate it describes,
+/// leaving the components to be checked for consistency with each other when it is built.
+fn parse_game_state(azul_fen: &str) -> Result<GameStateBuilder, ParseGameStateError> {
+    // Line endings, trailing spaces, and extra whitespace around separators are not significant
+    let sections: Vec<_> = azul_fen.trim().split('|').map(|s| s.trim()).collect();
+    let [
+        board_fens,
+        bowl_fens,
+        bag_fen,
+        final_section,
+        round_section @ ..,
+    ] = sections.as_slice()
+    else {
+        return Err(ParseGameStateError::Malformed);
+    };
+    // The round was added later, so older FENs without one default to zero
+    let round = match round_section {
+        [] => 0,
+        [round] => round.parse().or(Err(ParseGameStateError::Malformed))?,
+        _ => return Err(ParseGameStateError::Malformed),
+    };
+
+    let mut board_fens: Vec<_> = board_fens.split(";").map(|f| f.trim()).collect();
+    // Last FEN will always be empty since we split at ";" and each board ends with one
+    if board_fens.pop() != Some("") {
+        return Err(ParseGameStateError::Malformed);
+    }
+    let boards = board_fens
+        .into_iter()
+        .map(Board::from_azul_fen)
+        .collect::<Result<Vec<_>, ParseGameStateError>>()?;
+
+    let bowls = bowl_fens
+        .split_ascii_whitespace()
+        .map(Bowl::from_azul_fen)
+        .collect::<Result<Vec<_>, ParseGameStateError>>()?;
+
+    // A masked bag only gives the bag's contents, which are shuffled once loaded
+    let (masked, bag_fen) = match bag_fen.strip_prefix('*') {
+        Some(bag_fen) => (true, bag_fen),
+        None => (false, *bag_fen),
+    };
+    let mut bag = Bag::from_fen(&bag_fen.split_whitespace().collect::<String>())?;
+
+    let (active_player, first_token_owner, move_count, seed) = match final_section
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [active_player, first_token_owner, rest @ ..] if rest.len() <= 2 => (
+            active_player
+                .parse::<usize>()
+                .or(Err(ParseGameStateError::Malformed))?,
+            first_token_owner.parse::<usize>().map(Some).unwrap_or(None),
+            // The move count was added later, so older FENs without one default to zero
+            match rest.first() {
+                Some(count) => count
                     .parse::<usize>()
-                    .or(Err(ParseGameStateError))?,
-                first_token_owner.parse::<usize>().map(Some).unwrap_or(None),
-            ),
-            _ => return Err(ParseGameStateError),
-        };
-        Ok(GameState::builder()
-            .active_player(active_player)
-            .boards(boards)
-            .bowls(bowls)
-            .bag(bag)
-            .first_token_owner(first_token_owner)
-            .build())
+                    .or(Err(ParseGameStateError::Malformed))?,
+                None => 0,
+            },
+            // As is the optional seed for restocking the bag
+            match rest.get(1) {
+                Some(seed) => Some(
+                    seed.parse::<u64>()
+                        .or(Err(ParseGameStateError::Malformed))?,
+                ),
+                None => None,
+            },
+        ),
+        _ => return Err(ParseGameStateError::Malformed),
+    };
+    if let Some(seed) = seed {
+        bag.reseed(seed);
+    }
+    if masked {
+        let items = bag.items().clone();
+        bag.restock(items);
+    }
+    Ok(GameState::builder()
+        .active_player(active_player)
+        .boards(boards)
+        .bowls(bowls)
+        .bag(bag)
+        .first_token_owner(first_token_owner)
+        .move_count(move_count)
+        .round(round)
+        .seed(seed))
+}
+
+impl ToSeededAzulFEN for GameState {
+    /// Returns the AzulFEN encoding for this game state, annotated with a seed to be used for every
+    /// shuffle of the bag once it is reloaded, so reloaded games behave deterministically.
+    /// See the [AzulFEN protocol specification](crate::protocol) for details on the format.
+    fn to_azul_fen_with_seed(&self, seed: u64) -> String {
+        encode_azul_fen(self, &self.bag().fmt_uci_like(), Some(seed))
     }
 }
 
 impl ToAzulFEN for GameState {
     /// Returns the AzulFEN encoding for this game state.
+    /// The bag is encoded in its current order, which fully determines the upcoming draws, but not
+    /// how the bag is shuffled when it is restocked from the discard. If this game state is seeded,
+    /// as when it was loaded from a seeded AzulFEN, the seed is included so that restocks are also
+    /// reproduced once reloaded, as with [`ToSeededAzulFEN::to_azul_fen_with_seed`].
     /// See the [AzulFEN protocol specification](crate::protocol) for details on the format.
     fn to_azul_fen(&self) -> String {
-        // Boards
-        let mut azul_fen = String::new();
-        for board in self.boards().iter() {
-            azul_fen.push_str(&board.fmt_uci_like());
-            azul_fen.push(' ');
+        encode_azul_fen(self, &self.bag().fmt_uci_like(), *self.seed())
+    }
+}
+
+impl ToFairAzulFEN for GameState {
+    /// Returns the AzulFEN encoding for this game state with the bag masked, so that it gives the
+    /// tiles remaining in the bag in ascending order, but not the order they will be drawn in.
+    /// Everything else about the game state is public, and is encoded as usual.
+    /// Once reloaded, the masked bag is shuffled, so the game can still be played.
+    /// See the [AzulFEN protocol specification](crate::protocol) for details on the format.
+    fn to_fair_azul_fen(&self) -> String {
+        let mut tiles = self.bag().items().clone();
+        tiles.sort();
+        let tiles: String = tiles.iter().map(|t| t.to_string()).collect();
+        encode_azul_fen(self, &format!("*{}", tiles), None)
+    }
+}
+
+/// Encodes the given game state as AzulFEN, with the given bag component and optional seed.
+fn encode_azul_fen(state: &GameState, bag_fen: &str, seed: Option<u64>) -> String {
+    // Boards
+    let mut azul_fen = String::new();
+    for board in state.boards().iter() {
+        azul_fen.push_str(&board.fmt_uci_like());
+        azul_fen.push(' ');
+    }
+
+    // Bowls
+    azul_fen.push_str("| ");
+    for bowl in state.bowls().iter() {
+        azul_fen.push_str(&bowl.fmt_uci_like());
+        azul_fen.push(' ');
+    }
+
+    // Bag
+    azul_fen.push_str("| ");
+    azul_fen.push_str(bag_fen);
+
+    // Active player, first player token, move count, and seed
+    azul_fen.push_str(" | ");
+    azul_fen.push_str(&state.active_player().to_string());
+    azul_fen.push(' ');
+    azul_fen.push_str(&if let Some(t) = state.first_token_owner() {
+        t.to_string()
+    } else {
+        "-".to_string()
+    });
+    azul_fen.push(' ');
+    azul_fen.push_str(&state.move_count().to_string());
+    if let Some(seed) = seed {
+        azul_fen.push(' ');
+        azul_fen.push_str(&seed.to_string());
+    }
+
+    // Round
+    azul_fen.push_str(" | ");
+    azul_fen.push_str(&state.round().to_string());
+
+    azul_fen.push('\n');
+    azul_fen
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn move_count_round_trips() {
+        let mut state = GameState::new(2);
+        state.setup_next_round();
+        for _ in 0..3 {
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
         }
 
-        // Bowls
-        azul_fen.push_str("| ");
-        for bowl in self.bowls().iter() {
-            azul_fen.push_str(&bowl.fmt_uci_like());
-            azul_fen.push(' ');
+        let parsed = GameState::from_azul_fen(&state.to_azul_fen()).unwrap();
+        assert_eq!(*parsed.move_count(), 3);
+    }
+
+    #[test]
+    fn tolerates_line_endings_and_trailing_spaces() {
+        let mut state = GameState::new_seeded(2, 14);
+        state.setup_next_round();
+        let fen = state.to_azul_fen();
+        let bare = fen.trim_end();
+
+        let expected = GameState::from_azul_fen(bare).unwrap();
+        for variant in [
+            format!("{}\n", bare),
+            format!("{}\r\n", bare),
+            format!("{}   ", bare),
+            format!("{} \t\r\n", bare),
+        ] {
+            let parsed = GameState::from_azul_fen(&variant).unwrap();
+            assert_eq!(parsed.to_azul_fen(), expected.to_azul_fen());
+            assert!(parsed.position_eq(&expected));
         }
+    }
 
-        // Bag
-        azul_fen.push_str("| ");
-        azul_fen.push_str(&self.bag().fmt_uci_like());
+    #[test]
+    fn round_round_trips() {
+        let mut state = GameState::new_seeded(2, 2);
+        state.setup_next_round();
+        while *state.round() < 3 {
+            while !state.round_over() {
+                let choice = state.get_valid_moves()[0].clone();
+                state.make_move(&choice).unwrap();
+            }
+            state.setup_next_round();
+        }
+        let fen = state.to_azul_fen();
+        let parsed = GameState::from_azul_fen(&fen).unwrap();
+        assert_eq!(parsed.round(), state.round());
+        assert_eq!(parsed.to_azul_fen(), fen);
 
-        // Active player and first player token
-        azul_fen.push_str(" | ");
-        azul_fen.push_str(&self.active_player().to_string());
-        azul_fen.push(' ');
-        azul_fen.push_str(&if let Some(t) = self.first_token_owner() {
-            t.to_string()
-        } else {
-            "-".to_string()
-        });
-
-        azul_fen.push('\n');
-        azul_fen
+        // FENs from before the round was encoded are still accepted
+        let (old, _) = fen.rsplit_once('|').unwrap();
+        assert_eq!(*GameState::from_azul_fen(old).unwrap().round(), 0);
+    }
+
+    #[test]
+    fn finished_solo_games_stay_finished() {
+        let mut state = GameState::new_seeded(1, 4);
+        state.setup_next_round();
+        state.random_playout(&mut StdRng::seed_from_u64(4));
+        assert!(state.is_game_over());
+
+        let parsed = GameState::from_azul_fen(&state.to_azul_fen()).unwrap();
+        assert!(parsed.is_game_over());
+    }
+
+    #[test]
+    fn unsorted_bowls_give_unique_moves() {
+        let mut state = GameState::new_seeded(2, 19);
+        state.setup_next_round();
+        let fen = state.to_azul_fen();
+        let mut sections: Vec<String> = fen.split('|').map(String::from).collect();
+        // Write the first bowl's tiles in descending order instead
+        let mut bowls: Vec<String> = sections[1].split_whitespace().map(String::from).collect();
+        bowls[1] = bowls[1].chars().rev().collect();
+        assert!(!bowls[1].as_bytes().is_sorted());
+        sections[1] = format!(" {} ", bowls.join(" "));
+
+        let parsed = GameState::from_azul_fen(&sections.join("|")).unwrap();
+        assert_eq!(parsed.bowls()[1], state.bowls()[1]);
+        let moves = parsed.get_valid_moves();
+        for (i, choice) in moves.iter().enumerate() {
+            assert!(!moves[i + 1..].contains(choice), "{:?} repeated", choice);
+        }
+        assert_eq!(moves, state.get_valid_moves());
+    }
+
+    #[test]
+    fn overfull_holds_are_rejected() {
+        let board_fen = |held: &str| format!("5/5/5/5/5 {} 00000 00000 00000 0 0", held);
+        assert!(Board::from_azul_fen(&board_fen("0000000000")).is_ok());
+        assert!(Board::from_azul_fen(&board_fen("3100000025")).is_ok());
+        for held in ["3500000000", "3200000000", "0003000000", "0000000026"] {
+            assert_eq!(
+                Board::from_azul_fen(&board_fen(held)),
+                Err(ParseGameStateError::Malformed)
+            );
+        }
+    }
+
+    #[test]
+    fn parsed_placed_tiles_match_the_wall() {
+        let board =
+            Board::from_azul_fen("-4/1-3/--1--/5/----- 0000000000 00000 00000 00000 0 0").unwrap();
+        let mut count = 0;
+        for (row, placed) in board.placed().iter().enumerate() {
+            for (col, tile) in placed.iter().enumerate() {
+                if let Some(tile) = tile {
+                    assert_eq!(*tile, Board::get_tile_type_at_pos(row, col));
+                    count += 1;
+                }
+            }
+        }
+        assert_eq!(count, 11);
+        assert_eq!(board.is_valid(), Ok(()));
+    }
+
+    #[test]
+    fn validation_rejects_broken_fens() {
+        let mut state = GameState::new_seeded(2, 3);
+        state.setup_next_round();
+        let fen = state.to_azul_fen();
+        assert_eq!(GameState::validate_azul_fen(&fen), Ok(()));
+
+        // Every truncation, including ones which cut a hold in half, fails without panicking
+        for end in (0..fen.trim_end().len()).filter(|&end| fen.is_char_boundary(end)) {
+            let truncated = &fen[..end];
+            assert_eq!(
+                GameState::validate_azul_fen(truncated).is_ok(),
+                GameState::from_azul_fen(truncated).is_ok()
+            );
+        }
+        for broken in ["", "|||", "é | ü | ö | 0 -", &fen.replace('0', "٠")] {
+            assert!(GameState::validate_azul_fen(broken).is_err());
+            assert!(GameState::from_azul_fen(broken).is_err());
+        }
+        // Components which parse but don't fit together are also rejected, such as an active
+        // player without a board
+        let mut sections: Vec<_> = fen.split('|').collect();
+        sections[3] = " 7 - 0 ";
+        assert!(GameState::validate_azul_fen(&sections.join("|")).is_err());
+    }
+
+    #[test]
+    fn centre_state_round_trips() {
+        let mut state = GameState::new_seeded(2, 9);
+        state.setup_next_round();
+        // A take from a bowl moves its leftovers to the centre, which is still untouched
+        let from_bowl = state.get_valid_moves()[0].clone();
+        state.make_move(&from_bowl).unwrap();
+        assert_eq!(*state.first_token_owner(), None);
+        let untouched = state.to_azul_fen();
+        let parsed = GameState::from_azul_fen(&untouched).unwrap();
+        assert_eq!(parsed.to_azul_fen(), untouched);
+        assert_eq!(*parsed.first_token_owner(), None);
+
+        let from_centre = state
+            .get_valid_moves()
+            .into_iter()
+            .find(|choice| choice.bowl == 0)
+            .unwrap();
+        state.make_move(&from_centre).unwrap();
+        assert_eq!(*state.first_token_owner(), Some(1));
+        let taken = state.to_azul_fen();
+        let parsed = GameState::from_azul_fen(&taken).unwrap();
+        assert_eq!(parsed.to_azul_fen(), taken);
+        assert_eq!(*parsed.first_token_owner(), Some(1));
+    }
+
+    #[test]
+    fn checksums_tell_positions_apart() {
+        let mut state = GameState::new_seeded(2, 8);
+        state.setup_next_round();
+        let copy = GameState::from_azul_fen(&state.to_azul_fen()).unwrap();
+        assert_eq!(copy.checksum(), state.checksum());
+        assert_eq!(state.clone().checksum(), state.checksum());
+
+        let choice = state.get_valid_moves()[0].clone();
+        let moved = state.with_move(&choice).unwrap();
+        assert_ne!(moved.checksum(), state.checksum());
+    }
+
+    #[test]
+    fn bonus_segments_must_have_a_bonus_each() {
+        // Boards are separated by a marker in a full AzulFEN, which isn't part of the component
+        let fen = Board::default().fmt_uci_like();
+        let fen = fen.trim_end_matches(';');
+        let parts: Vec<_> = fen.split_whitespace().collect();
+        assert!(Board::from_azul_fen(fen).is_ok());
+
+        let sections = [
+            (2, BonusSection::Rows),
+            (3, BonusSection::Columns),
+            (4, BonusSection::Colors),
+        ];
+        for (idx, section) in sections {
+            for got in [4, 6] {
+                let mut broken = parts.clone();
+                let segment = "0".repeat(got);
+                broken[idx] = &segment;
+                assert_eq!(
+                    Board::from_azul_fen(&broken.join(" ")),
+                    Err(ParseGameStateError::BadBonusLength {
+                        section,
+                        got,
+                        expected: 5
+                    })
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn seeded_fen_keeps_seed() {
+        let mut state = GameState::new_seeded(2, 6);
+        state.setup_next_round();
+        let fen = state.to_azul_fen_with_seed(42);
+
+        let mut reloaded = GameState::from_azul_fen(&fen).unwrap();
+        assert_eq!(*reloaded.seed(), Some(42));
+        assert_eq!(reloaded.to_azul_fen(), fen);
+
+        // Both reloads restock the bag identically, so they deal the same next rounds
+        let mut again = GameState::from_azul_fen(&reloaded.to_azul_fen()).unwrap();
+        for state in [&mut reloaded, &mut again] {
+            for _ in 0..3 {
+                while !state.round_over() {
+                    let choice = state.get_valid_moves()[0].clone();
+                    state.make_move(&choice).unwrap();
+                }
+                state.setup_next_round();
+            }
+        }
+        assert_eq!(reloaded.bowls(), again.bowls());
+    }
+
+    #[test]
+    fn fair_fen_round_trips_public_state() {
+        let mut state = GameState::new_seeded(3, 11);
+        state.setup_next_round();
+        let fair = state.to_fair_azul_fen();
+
+        let parsed = GameState::from_azul_fen(&fair).unwrap();
+        assert_eq!(parsed.to_fair_azul_fen(), fair);
+        assert_eq!(parsed.bag().histogram(), state.bag().histogram());
+
+        // The bag only lists its tiles in ascending order
+        let bag_fen = fair.split('|').nth(2).unwrap().trim();
+        let mut tiles: Vec<_> = state.bag().items().clone();
+        tiles.sort();
+        let sorted: String = tiles.iter().map(|t| t.to_string()).collect();
+        assert_eq!(bag_fen, format!("*{}", sorted));
+        assert_ne!(state.to_azul_fen(), fair);
     }
 }
@@ -1,9 +1,13 @@
-use azul_movegen::{Row, Tile, game_move::Move};
+use azul_movegen::{
+    Row, Tile,
+    board::{BOARD_DIMENSION, TILE_TYPES},
+    game_move::Move,
+};
 use clap::{Parser, ValueEnum};
-use std::num::ParseIntError;
+use std::{num::ParseIntError, path::Path};
 
 #[derive(Debug, Clone)]
-struct EngineConfig {
+pub struct EngineConfig {
     pub path: String,
     pub proto: Protocol,
     pub tc: Option<TimeControl>,
@@ -15,22 +19,32 @@ struct EngineConfig {
 }
 
 #[derive(Debug, Clone)]
-enum TimeControl {
+pub enum TimeControl {
     Increment(u32, u32),
     Fixed(u32),
 }
 
-#[derive(ValueEnum, Clone)]
-enum TournamentStyle {
+#[derive(Debug, ValueEnum, Clone, Copy, PartialEq)]
+pub enum TournamentStyle {
     Gauntlet,
     RoundRobin,
     Swiss,
     Random,
 }
 
+/// The format completed games are written to `--out` in.
+#[derive(Debug, Clone, Copy, PartialEq, Default, ValueEnum)]
+pub enum OutFormat {
+    /// A line of text describing each game.
+    #[default]
+    Text,
+    /// A JSON object on its own line for each game, see [`GameRecord::to_json_line`](crate::record::GameRecord::to_json_line).
+    Jsonl,
+}
+
 #[derive(Parser)]
 #[command(name = "azul-interface", about = "Manages Azul engine matches")]
-struct Cli {
+pub struct Cli {
     // =====================
     // Engines
     // =====================
@@ -44,41 +58,50 @@ struct Cli {
     pub tournament: Option<TournamentStyle>,
 
     #[arg(long, value_name = "N")]
-    pub concurrency: usize,
+    pub concurrency: Option<usize>,
 
     #[arg(long, value_name = "PATH")]
-    pub out: String,
+    pub out: Option<String>,
+
+    #[arg(long = "out-format", value_enum, default_value_t = OutFormat::Text)]
+    pub out_format: OutFormat,
 
     #[arg(long, value_name = "PATH")]
-    pub resume: String,
+    pub resume: Option<String>,
 
     #[arg(long, value_name = "N")]
-    pub rounds: usize,
+    pub rounds: Option<usize>,
 
     #[arg(long, value_name = "N")]
-    pub games: usize,
+    pub games: Option<usize>,
 
     #[arg(long, action)]
     pub repeat: bool,
 
     #[arg(long = "max-games", value_name = "N")]
-    pub max_games: usize,
+    pub max_games: Option<usize>,
 
     #[arg(long, value_name = "N")]
-    pub seed: u64,
+    pub seed: Option<u64>,
 
     #[arg(long, value_name = "PATH")]
-    pub openings: String,
+    pub openings: Option<String>,
 
     #[arg(long, action)]
     pub swap: bool,
 
     #[arg(long, value_name = "N")]
-    pub timeout: usize,
+    pub timeout: Option<u64>,
 
     #[arg(long, action)]
     pub recover: bool,
 
+    #[arg(long = "adjudicate-moves", value_name = "N")]
+    pub adjudicate_moves: Option<usize>,
+
+    #[arg(long = "resign-score", value_name = "DELTA")]
+    pub resign_score: Option<usize>,
+
     // =====================
     // Debugging and logging
     // =====================
@@ -144,7 +167,7 @@ fn parse_engine(s: &str) -> Result<EngineConfig, String> {
                     let increment = inc.parse::<u32>().map_err(|_| "Invalid increment")?;
                     config.tc = Some(TimeControl::Increment(base, increment));
                 } else {
-                    let base = s.parse::<u32>().map_err(|_| "Invalid time format")?;
+                    let base = val.parse::<u32>().map_err(|_| "Invalid time format")?;
                     config.tc = Some(TimeControl::Increment(base, 0));
                 }
             }
@@ -159,12 +182,16 @@ fn parse_engine(s: &str) -> Result<EngineConfig, String> {
             "dir" => config.dir = Some(val.to_string()),
             "args" => config.args = Some(val.to_string()),
             "name" => config.name = Some(val.to_string()),
-            "limit_mem" => config.limit_mem = val.parse().ok(),
-            "limit_threads" => config.limit_threads = val.parse().ok(),
+            "limit_mem" | "limit-mem" => config.limit_mem = val.parse().ok(),
+            "limit_threads" | "limit-threads" => config.limit_threads = val.parse().ok(),
             _ => return Err(format!("Unknown engine key: {}", key)),
         };
     }
 
+    // Humans are played from the terminal, so they have no executable and are not timed
+    if matches!(config.proto, Protocol::Human) {
+        return Ok(config);
+    }
     if config.path.is_empty() {
         return Err("Missing required key: path".to_string());
     } else if config.tc.is_none() {
@@ -174,8 +201,48 @@ fn parse_engine(s: &str) -> Result<EngineConfig, String> {
     Ok(config)
 }
 
-pub fn full_parse() {
-    let cli = Cli::parse();
+impl EngineConfig {
+    /// Returns the name this engine is shown under, which is its configured name if it has one,
+    /// or otherwise the file name of its executable. Humans without a name are shown as `human`.
+    pub fn display_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        match Path::new(&self.path).file_name() {
+            Some(file_name) => file_name.to_string_lossy().into_owned(),
+            None if matches!(self.proto, Protocol::Human) => String::from("human"),
+            None => self.path.clone(),
+        }
+    }
+}
+
+/// Returns the display name of each of the given engines, in order.
+/// Engines which would otherwise share a name, such as the same executable configured twice, are
+/// numbered in order of appearance from their second occurrence, as in `engine (2)`.
+pub fn engine_names(configs: &[EngineConfig]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(configs.len());
+    for config in configs {
+        let name = config.display_name();
+        let mut unique = name.clone();
+        let mut occurrence = 1;
+        while names.contains(&unique) {
+            occurrence += 1;
+            unique = format!("{} ({})", name, occurrence);
+        }
+        names.push(unique);
+    }
+    names
+}
+
+impl Cli {
+    /// Whether a running score line should be printed after each completed game of a tournament.
+    pub fn live_updates(&self) -> bool {
+        self.log && !self.quiet
+    }
+}
+
+pub fn full_parse() -> Cli {
+    Cli::parse()
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -184,12 +251,49 @@ pub enum Protocol {
     UAI,
 }
 
-#[derive(Debug)]
-pub struct ParseMoveError;
+/// Describes why a move could not be parsed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseMoveError {
+    /// The move was not six digits long, or compact moves were not in pairs of bytes. Holds the
+    /// length found.
+    BadLength(usize),
+    /// A part of the move was not a number.
+    NotNumeric,
+    /// The row is beyond the bottom of the board. Holds the row found.
+    RowOutOfRange(usize),
+    /// The tile type is not any known tile type. Holds the tile type found.
+    TileTypeOutOfRange(usize),
+}
 
 impl From<ParseIntError> for ParseMoveError {
     fn from(_: ParseIntError) -> Self {
-        ParseMoveError
+        ParseMoveError::NotNumeric
+    }
+}
+
+impl std::fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseMoveError::BadLength(len) => {
+                write!(
+                    f,
+                    "moves must be 6 digits long, but got a length of {}",
+                    len
+                )
+            }
+            ParseMoveError::NotNumeric => write!(f, "moves must only contain digits"),
+            ParseMoveError::RowOutOfRange(row) => write!(
+                f,
+                "row {} is beyond the board, rows are 00 for the floor or 01 to {:02}",
+                row, BOARD_DIMENSION
+            ),
+            ParseMoveError::TileTypeOutOfRange(tile_type) => write!(
+                f,
+                "tile type {} does not exist, tile types are 00 to {:02}",
+                tile_type,
+                TILE_TYPES - 1
+            ),
+        }
     }
 }
 
@@ -197,10 +301,15 @@ impl From<ParseIntError> for ParseMoveError {
 Here we expect moves in the format of `bowl, tile_type, row` where each input is a two-digit number
 ex. 040102 would correspond to the fourth bowl, first tile type, and second row of our own board
 Note: Bowl 00 will always correspond to the centre area, and row 00 will always correspond to the penalty area
+Tile types and rows beyond the board are rejected here, rather than when the move is played
 */
 pub fn parse_move(input: &str) -> Result<Move, ParseMoveError> {
+    // Splitting by bytes is only safe when every character is a single byte
+    if !input.is_ascii() {
+        return Err(ParseMoveError::NotNumeric);
+    }
     if input.len() != 6 {
-        return Err(ParseMoveError);
+        return Err(ParseMoveError::BadLength(input.len()));
     }
     let (bowl, other) = input.split_at(2);
     let (tile_type, row) = other.split_at(2);
@@ -208,14 +317,142 @@ pub fn parse_move(input: &str) -> Result<Move, ParseMoveError> {
     let bowl = bowl.parse::<usize>()?;
     let tile_type = tile_type.parse::<Tile>()?;
     let row = row.parse::<usize>()?;
-    let row = if row == 0 {
-        Row::Floor
-    } else {
-        Row::Wall(row - 1)
-    };
+    if tile_type >= TILE_TYPES {
+        return Err(ParseMoveError::TileTypeOutOfRange(tile_type));
+    }
+    if row > BOARD_DIMENSION {
+        return Err(ParseMoveError::RowOutOfRange(row));
+    }
+    let row = Row::from_protocol(row);
     Ok(Move {
         bowl,
         tile_type,
         row,
     })
 }
+
+/*
+Compact moves are packed into two bytes each, for storing long sequences of moves such as self-play games
+The first byte is the bowl, and the second byte holds the tile type in its high four bits and the row in its low four bits
+Rows are numbered as in `parse_move`, so row 0 corresponds to the penalty area
+*/
+pub fn moves_to_compact(moves: &[Move]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(moves.len() * 2);
+    for choice in moves {
+        let row = choice.row.to_protocol();
+        assert!(
+            choice.bowl <= u8::MAX as usize && choice.tile_type < 16 && row < 16,
+            "Move is too large to pack"
+        );
+        output.push(choice.bowl as u8);
+        output.push(((choice.tile_type as u8) << 4) | row as u8);
+    }
+    output
+}
+
+/// Unpacks moves packed by [`moves_to_compact`]. Tile types and rows beyond the board are
+/// rejected, as in [`parse_move`].
+pub fn moves_from_compact(input: &[u8]) -> Result<Vec<Move>, ParseMoveError> {
+    let (chunks, []) = input.as_chunks::<2>() else {
+        return Err(ParseMoveError::BadLength(input.len()));
+    };
+    chunks
+        .iter()
+        .map(|&[bowl, packed]| {
+            let tile_type = (packed >> 4) as Tile;
+            let row = (packed & 0x0F) as usize;
+            if tile_type >= TILE_TYPES {
+                return Err(ParseMoveError::TileTypeOutOfRange(tile_type));
+            }
+            if row > BOARD_DIMENSION {
+                return Err(ParseMoveError::RowOutOfRange(row));
+            }
+            Ok(Move {
+                bowl: bowl as usize,
+                tile_type,
+                row: Row::from_protocol(row),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use azul_movegen::GameState;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn compact_moves_round_trip() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut state = GameState::new_seeded(2, 3);
+        state.set_record_history(true);
+        state.setup_next_round();
+        state.random_playout(&mut rng);
+        let moves = state.history();
+
+        let packed = moves_to_compact(moves);
+        assert_eq!(packed.len(), moves.len() * 2);
+        assert_eq!(&moves_from_compact(&packed).unwrap(), moves);
+    }
+
+    #[test]
+    fn compact_moves_reject_out_of_range() {
+        assert_eq!(
+            moves_from_compact(&[1, 0x10, 2]),
+            Err(ParseMoveError::BadLength(3))
+        );
+        assert_eq!(
+            moves_from_compact(&[1, (TILE_TYPES as u8) << 4]),
+            Err(ParseMoveError::TileTypeOutOfRange(TILE_TYPES))
+        );
+        assert_eq!(
+            moves_from_compact(&[1, BOARD_DIMENSION as u8 + 1]),
+            Err(ParseMoveError::RowOutOfRange(BOARD_DIMENSION + 1))
+        );
+        assert!(moves_from_compact(&[1, BOARD_DIMENSION as u8]).is_ok());
+    }
+
+    #[test]
+    fn parses_time_controls() {
+        let increment = parse_engine("path=engine tc=300+5").unwrap();
+        assert!(matches!(increment.tc, Some(TimeControl::Increment(300, 5))));
+
+        let base_only = parse_engine("path=engine tc=60").unwrap();
+        assert!(matches!(base_only.tc, Some(TimeControl::Increment(60, 0))));
+
+        let fixed = parse_engine("path=engine st=10").unwrap();
+        assert!(matches!(fixed.tc, Some(TimeControl::Fixed(10))));
+
+        assert!(parse_engine("path=engine tc=60 st=10").is_err());
+    }
+
+    #[test]
+    fn moves_beyond_the_board_are_rejected() {
+        assert!(parse_move("010405").is_ok());
+        assert_eq!(parse_move("010406"), Err(ParseMoveError::RowOutOfRange(6)));
+        assert_eq!(parse_move("010099"), Err(ParseMoveError::RowOutOfRange(99)));
+        assert_eq!(
+            parse_move("010501"),
+            Err(ParseMoveError::TileTypeOutOfRange(5))
+        );
+    }
+
+    #[test]
+    fn move_errors_name_what_went_wrong() {
+        assert_eq!(parse_move("0101"), Err(ParseMoveError::BadLength(4)));
+        assert_eq!(parse_move("01010100"), Err(ParseMoveError::BadLength(8)));
+        assert_eq!(parse_move("01x101"), Err(ParseMoveError::NotNumeric));
+        assert_eq!(parse_move("01٠101"), Err(ParseMoveError::NotNumeric));
+        assert_eq!(parse_move("010107"), Err(ParseMoveError::RowOutOfRange(7)));
+        assert_eq!(
+            parse_move("010901"),
+            Err(ParseMoveError::TileTypeOutOfRange(9))
+        );
+        assert_eq!(
+            ParseMoveError::BadLength(4).to_string(),
+            "moves must be 6 digits long, but got a length of 4"
+        );
+    }
+}
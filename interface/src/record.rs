@@ -0,0 +1,265 @@
+use azul_movegen::{GameResult, GameState, Move};
+
+use crate::parsing::{FromAzulFEN, ToAzulFEN};
+
+/// Describes how a game ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Termination {
+    /// The game was played to its natural end.
+    Normal,
+    /// A player failed to reply with a move within their time, and lost.
+    TimeForfeit,
+    /// A player replied with an illegal move, and lost.
+    IllegalMove,
+    /// A player's engine stopped responding or exited, and lost.
+    Crash,
+    /// The game reached the move limit and was decided by the current scores.
+    MoveLimit,
+    /// A player resigned, and lost.
+    Resignation,
+    /// Every player offered a draw, and the game was drawn between them all.
+    DrawAgreed,
+    /// At a round boundary, the leading player was too far ahead for anyone to catch up, and won.
+    ScoreAdjudicated,
+}
+
+impl Termination {
+    /// Returns the name of this termination used in machine-readable output, ex. `time_forfeit`.
+    /// These names are stable, so they can be relied on by other tools.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Termination::Normal => "normal",
+            Termination::TimeForfeit => "time_forfeit",
+            Termination::IllegalMove => "illegal_move",
+            Termination::Crash => "crash",
+            Termination::MoveLimit => "move_limit",
+            Termination::Resignation => "resignation",
+            Termination::DrawAgreed => "draw_agreed",
+            Termination::ScoreAdjudicated => "score_adjudicated",
+        }
+    }
+}
+
+/// A complete record of a played game.
+/// # Properties
+/// * `players`: the name of the engine in each seat, in seat order.
+/// * `seed`: the seed the game was created with, which reproduces every draw from the bag.
+/// * `swapped`: whether the players were swapped once the first round was dealt, as for the second
+///   game of a pair, so that the seed alone reproduces the deal but not the seating.
+/// * `start_fen`: the AzulFEN of the position after the first round was dealt.
+/// * `moves`: every move played, in order.
+/// * `result`: the outcome of the game.
+/// * `scores`: the score of each player when the game ended, in seat order.
+/// * `rounds_played`: the number of rounds set up during the game, as counted by the gamestate.
+/// * `termination`: how the game ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    pub players: Vec<String>,
+    pub seed: u64,
+    pub swapped: bool,
+    pub start_fen: String,
+    pub moves: Vec<Move>,
+    pub result: GameResult,
+    pub scores: Vec<usize>,
+    pub rounds_played: usize,
+    pub termination: Termination,
+}
+
+impl GameRecord {
+    /// Returns a one-line text summary of this game, naming the players, the result, how the game
+    /// ended, the final scores, and the seed, noting whether the players were swapped.
+    pub fn to_text_line(&self) -> String {
+        format!(
+            "{}: {} ({}), scores {}, seed {}{}",
+            self.players.join(" vs "),
+            self.result_line(),
+            self.termination.key(),
+            self.scores
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join("-"),
+            self.seed,
+            if self.swapped { " swapped" } else { "" }
+        )
+    }
+
+    /// Returns this game as a single-line JSON object, for appending to a JSON Lines file.
+    ///
+    /// The object has the following keys, always in this order:
+    /// * `engines`: the name of the engine in each seat, in seat order.
+    /// * `seed`: the seed the game was created with.
+    /// * `swapped`: whether the players were swapped after the first deal.
+    /// * `opening`: the AzulFEN of the starting position.
+    /// * `result`: either `"win"` or `"draw"`.
+    /// * `winners`: the seats of the winner, or of every player sharing the draw.
+    /// * `scores`: the final score of each seat.
+    /// * `rounds`: the number of rounds played.
+    /// * `termination`: how the game ended, as given by [`Termination::key`].
+    pub fn to_json_line(&self) -> String {
+        let (result, winners) = match &self.result {
+            GameResult::Win(winner) => ("win", vec![*winner]),
+            GameResult::Draw(winners) => ("draw", winners.clone()),
+        };
+        let list = |items: Vec<String>| format!("[{}]", items.join(","));
+        format!(
+            "{{\"engines\":{},\"seed\":{},\"swapped\":{},\"opening\":{},\"result\":\"{}\",\"winners\":{},\"scores\":{},\"rounds\":{},\"termination\":\"{}\"}}",
+            list(self.players.iter().map(|p| json_string(p)).collect()),
+            self.seed,
+            self.swapped,
+            json_string(self.start_fen.trim_end()),
+            result,
+            list(winners.iter().map(|w| w.to_string()).collect()),
+            list(self.scores.iter().map(|s| s.to_string()).collect()),
+            self.rounds_played,
+            self.termination.key()
+        )
+    }
+
+    /// Returns the winner's final score minus the best final score among the other players, or
+    /// zero if there is only one player. In a draw, the score of the first player sharing it is
+    /// used. The margin is negative if the winner didn't have the highest score, as when a game is
+    /// won by forfeit.
+    pub fn point_margin(&self) -> i32 {
+        let winner = match &self.result {
+            GameResult::Win(winner) => *winner,
+            GameResult::Draw(winners) => winners.first().copied().unwrap_or_default(),
+        };
+        let runner_up = self
+            .scores
+            .iter()
+            .enumerate()
+            .filter(|&(seat, _)| seat != winner)
+            .map(|(_, &score)| score)
+            .max();
+        match (self.scores.get(winner), runner_up) {
+            (Some(&best), Some(next)) => best as i32 - next as i32,
+            _ => 0,
+        }
+    }
+
+    /// Returns the AzulFEN of the opening this game was played from, as it was before the players
+    /// were swapped, so that both games of a swapped pair share the same opening.
+    /// If the starting AzulFEN can't be parsed, it is returned as it is.
+    pub fn opening_fen(&self) -> String {
+        if !self.swapped {
+            return self.start_fen.clone();
+        }
+        match GameState::from_azul_fen(&self.start_fen) {
+            Ok(start) => start.with_swapped_players().to_azul_fen(),
+            Err(_) => self.start_fen.clone(),
+        }
+    }
+
+    /// Returns a one-line description of the result, naming the players involved.
+    pub fn result_line(&self) -> String {
+        match &self.result {
+            GameResult::Win(winner) => format!("{} wins", self.players[*winner]),
+            GameResult::Draw(winners) => format!(
+                "Draw between {}",
+                winners
+                    .iter()
+                    .map(|&w| self.players[w].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Returns the given text as a quoted JSON string, escaping it as required.
+fn json_string(text: &str) -> String {
+    let mut output = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a record filled with placeholder values, noting whether the players were swapped.
+    fn record_of(swapped: bool) -> GameRecord {
+        GameRecord {
+            players: vec!["a".to_string(), "b".to_string()],
+            seed: 42,
+            swapped,
+            start_fen: String::new(),
+            moves: Vec::new(),
+            result: GameResult::Win(0),
+            scores: vec![10, 4],
+            rounds_played: 5,
+            termination: Termination::Normal,
+        }
+    }
+
+    #[test]
+    fn json_lines_hold_every_field() {
+        let won = GameRecord {
+            start_fen: "fen \"quoted\"\n".to_string(),
+            ..record_of(false)
+        };
+        let drawn = GameRecord {
+            result: GameResult::Draw(vec![0, 1]),
+            scores: vec![12, 12],
+            termination: Termination::DrawAgreed,
+            ..record_of(true)
+        };
+        let lines = [won.to_json_line(), drawn.to_json_line()].join("\n");
+        let lines: Vec<_> = lines.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"engines":["a","b"],"seed":42,"swapped":false,"opening":"fen \"quoted\"","result":"win","winners":[0],"scores":[10,4],"rounds":5,"termination":"normal"}"#,
+                format!(
+                    r#"{{"engines":["a","b"],"seed":42,"swapped":true,"opening":"","result":"draw","winners":[0,1],"scores":[12,12],"rounds":5,"termination":"{}"}}"#,
+                    Termination::DrawAgreed.key()
+                )
+                .as_str(),
+            ]
+        );
+    }
+
+    #[test]
+    fn point_margin_is_the_winners_lead() {
+        let record = GameRecord {
+            scores: vec![30, 18],
+            ..record_of(false)
+        };
+        assert_eq!(record.point_margin(), 12);
+
+        let upset = GameRecord {
+            result: GameResult::Win(1),
+            ..record
+        };
+        assert_eq!(upset.point_margin(), -12);
+    }
+
+    #[test]
+    fn swapped_games_share_their_opening() {
+        let mut start = GameState::new_seeded(2, 42);
+        start.setup_next_round();
+        let first = GameRecord {
+            start_fen: start.to_azul_fen(),
+            ..record_of(false)
+        };
+        let second = GameRecord {
+            start_fen: start.with_swapped_players().to_azul_fen(),
+            ..record_of(true)
+        };
+        assert_ne!(first.start_fen, second.start_fen);
+        assert_eq!(first.opening_fen(), first.start_fen);
+        assert_eq!(second.opening_fen(), first.start_fen);
+    }
+}
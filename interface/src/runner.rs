@@ -0,0 +1,757 @@
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use azul_movegen::{GameResult, GameState};
+
+use crate::{
+    parsing::ToAzulFEN,
+    protocol::{TimeControl, parse_move},
+    record::{GameRecord, Termination},
+};
+
+/// Describes why an engine failed to reply with a move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EngineError {
+    /// The engine did not reply within its time.
+    Timeout,
+    /// The engine stopped responding or exited.
+    Crash,
+}
+
+/// The time an engine is given to reply with its next move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveTime {
+    /// The same time is given for every move, and unused time is not carried over.
+    Fixed(Duration),
+    /// The engine has a running clock for the whole game, which gains the increment after each
+    /// move. The engine may use all of the remaining time on a single move.
+    Clock {
+        remaining: Duration,
+        increment: Duration,
+    },
+}
+
+impl MoveTime {
+    /// Returns the time given for the first move under the given time control, or an unlimited
+    /// time if there is no time control.
+    pub fn start(time_control: Option<&TimeControl>) -> Self {
+        match time_control {
+            Some(TimeControl::Increment(base, increment)) => MoveTime::Clock {
+                remaining: Duration::from_secs(*base as u64),
+                increment: Duration::from_secs(*increment as u64),
+            },
+            Some(TimeControl::Fixed(millis)) => {
+                MoveTime::Fixed(Duration::from_millis(*millis as u64))
+            }
+            None => MoveTime::Fixed(Duration::MAX),
+        }
+    }
+
+    /// Returns the most time which may be taken on the next move.
+    pub fn limit(&self) -> Duration {
+        match self {
+            MoveTime::Fixed(time) => *time,
+            MoveTime::Clock { remaining, .. } => *remaining,
+        }
+    }
+
+    /// Returns the time left for the current move after the given time has been spent on it.
+    fn spend(self, elapsed: Duration) -> Self {
+        match self {
+            MoveTime::Fixed(time) => MoveTime::Fixed(time.saturating_sub(elapsed)),
+            MoveTime::Clock {
+                remaining,
+                increment,
+            } => MoveTime::Clock {
+                remaining: remaining.saturating_sub(elapsed),
+                increment,
+            },
+        }
+    }
+
+    /// Returns the time given for the move after one which took the given time.
+    pub fn after_move(self, elapsed: Duration) -> Self {
+        match self {
+            MoveTime::Fixed(_) => self,
+            MoveTime::Clock {
+                remaining,
+                increment,
+            } => MoveTime::Clock {
+                remaining: remaining.saturating_sub(elapsed) + increment,
+                increment,
+            },
+        }
+    }
+}
+
+/// An engine taking part in a match, such as an engine subprocess or a scripted opponent.
+pub trait Engine {
+    /// The name this engine is shown under in records and results.
+    fn name(&self) -> &str;
+
+    /// Asks the engine for its move in the given gamestate, to be made within the given time.
+    /// The engine replies in the UAI-like form `bestmove <move>`, or with `bestmove resign` or
+    /// `bestmove draw`.
+    fn go(&mut self, state: &GameState, time: MoveTime) -> Result<String, EngineError>;
+
+    /// Whether this engine forfeits the game by taking longer than its time to reply.
+    fn timed(&self) -> bool {
+        true
+    }
+}
+
+/// Receives progress updates while a series of games is played, see [`play_games`].
+pub trait ProgressReporter {
+    /// Called each time a game of the series finishes, with the number of games finished so far,
+    /// the total number of games in the series, and the record of the game which just finished.
+    fn on_game_complete(&mut self, done: usize, total: usize, last: &GameRecord);
+}
+
+/// Reports progress by writing a line for each finished game, holding the percentage of the
+/// series finished and the result of the game.
+pub struct ConsoleProgress<W: Write> {
+    out: W,
+}
+
+impl<W: Write> ConsoleProgress<W> {
+    /// Creates a new reporter which writes to the given output.
+    pub fn new(out: W) -> Self {
+        ConsoleProgress { out }
+    }
+
+    /// Consumes this reporter, returning its output.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> ProgressReporter for ConsoleProgress<W> {
+    /// Writes a line in the form `[ 50%] 1/2 games, A wins (normal)`.
+    /// Progress is only informational, so failing to write it is ignored.
+    fn on_game_complete(&mut self, done: usize, total: usize, last: &GameRecord) {
+        let percent = (done * 100).checked_div(total).unwrap_or(100);
+        let _ = writeln!(
+            self.out,
+            "[{:>3}%] {}/{} games, {} ({})",
+            percent,
+            done,
+            total,
+            last.result_line(),
+            last.termination.key()
+        );
+    }
+}
+
+/// Settings which apply to every game of a match.
+/// # Properties
+/// * `time_controls`: the time control of each engine, in the same order as the engines. Engines
+///   without a time control are given unlimited time.
+/// * `adjudicate_moves`: the number of moves after which an unfinished game is decided by the
+///   current scores, if any.
+/// * `resign_score`: the lead, in points, at which a game is decided at a round boundary if no
+///   other player can catch up, if any. See [`insurmountable_leader`].
+#[derive(Debug, Clone)]
+pub struct MatchConfig {
+    pub time_controls: Vec<Option<TimeControl>>,
+    pub adjudicate_moves: Option<usize>,
+    pub resign_score: Option<usize>,
+}
+
+/// Plays a single game between the given engines, seated in the given order, from a fresh deal
+/// with the given seed. See [`play_game_seated`] for details.
+pub fn play_game(engines: &mut [&mut dyn Engine], seed: u64, config: &MatchConfig) -> GameRecord {
+    let seats: Vec<_> = (0..engines.len()).collect();
+    play_game_seated(engines, &seats, seed, config)
+}
+
+/// Plays a single game from a fresh deal with the given seed, where `seats` gives the index of the
+/// engine controlling each player, in player order. An engine may control several players.
+///
+/// On each turn, only the engine controlling the active player is asked for a move, so the player
+/// holding the first player token is correctly the first to be asked each round.
+/// Each engine plays under its own time control. A fixed time control gives the same time for
+/// every move, while an increment time control keeps a clock for the engine across the game.
+/// An engine which fails to reply with a legal move in time, or crashes, immediately loses the game.
+/// A game which runs past the move limit, if one is set, is decided by the current scores.
+/// If a resign score is set, a game whose leader is that far ahead at a round boundary, and can't
+/// be caught, is won by the leader.
+/// An engine may resign instead of moving, which loses the game. It may also offer a draw, after
+/// which it is asked again for its move within the rest of its time. An offer stands until the
+/// offering player's next turn, and the game is drawn once every player has an offer standing.
+/// The record notes how the game ended, so these forfeits can be told apart from normal results.
+pub fn play_game_seated(
+    engines: &mut [&mut dyn Engine],
+    seats: &[usize],
+    seed: u64,
+    config: &MatchConfig,
+) -> GameRecord {
+    let mut start = GameState::new_seeded(seats.len(), seed);
+    start.setup_next_round();
+    play_game_from(engines, seats, start, config)
+}
+
+/// Plays a game from each of the given positions in turn, as in [`play_game_from`], with the
+/// same engines and seats for every game. The reporter is told as each game finishes.
+/// Returns the record of each game, in the order the positions were given.
+pub fn play_games(
+    engines: &mut [&mut dyn Engine],
+    seats: &[usize],
+    starts: Vec<GameState>,
+    config: &MatchConfig,
+    reporter: &mut dyn ProgressReporter,
+) -> Vec<GameRecord> {
+    let total = starts.len();
+    let mut records = Vec::with_capacity(total);
+    for start in starts {
+        let record = play_game_from(engines, seats, start, config);
+        reporter.on_game_complete(records.len() + 1, total, &record);
+        records.push(record);
+    }
+    records
+}
+
+/// Plays a single game from the given position, which should be seeded so that the record's
+/// seed reproduces its future draws. An unseeded position is recorded with a seed of 0.
+/// As in [`play_game_seated`], `seats` gives the index of the engine controlling each player.
+/// Only the moves played from the given position are recorded.
+pub fn play_game_from(
+    engines: &mut [&mut dyn Engine],
+    seats: &[usize],
+    start: GameState,
+    config: &MatchConfig,
+) -> GameRecord {
+    assert!(
+        seats.iter().all(|&engine| engine < engines.len()),
+        "Seat given to a nonexistent engine"
+    );
+    assert_eq!(
+        seats.len(),
+        start.player_count(),
+        "Seat count doesn't match the players"
+    );
+    let seed = start.seed().unwrap_or_default();
+    let swapped = *start.players_swapped();
+    let start_fen = start.to_azul_fen();
+    let moves_before = start.history().len();
+    let mut gamestate = start;
+    gamestate.set_record_history(true);
+
+    let players: Vec<_> = seats
+        .iter()
+        .map(|&engine| engines[engine].name().to_string())
+        .collect();
+    let finish = |gamestate: &GameState, result, termination| GameRecord {
+        players: players.clone(),
+        seed,
+        swapped,
+        start_fen: start_fen.clone(),
+        moves: gamestate.history()[moves_before..].to_vec(),
+        result,
+        scores: gamestate.scores().collect(),
+        rounds_played: *gamestate.round(),
+        termination,
+    };
+
+    let mut clocks: Vec<_> = (0..engines.len())
+        .map(|engine| MoveTime::start(config.time_controls.get(engine).and_then(|tc| tc.as_ref())))
+        .collect();
+    let mut draw_offers = vec![false; seats.len()];
+
+    while !gamestate.is_game_over() {
+        if config
+            .adjudicate_moves
+            .is_some_and(|cap| *gamestate.move_count() >= cap)
+        {
+            return finish(&gamestate, gamestate.result(), Termination::MoveLimit);
+        }
+
+        let player = *gamestate.active_player();
+        let engine = seats[player];
+        draw_offers[player] = false;
+        let start = Instant::now();
+        let reply = loop {
+            let reply = engines[engine].go(&gamestate, clocks[engine].spend(start.elapsed()));
+            let reply = match reply {
+                Ok(reply)
+                    if !engines[engine].timed() || start.elapsed() <= clocks[engine].limit() =>
+                {
+                    reply
+                }
+                Ok(_) | Err(EngineError::Timeout) => {
+                    return finish(
+                        &gamestate,
+                        forfeit(seats.len(), player),
+                        Termination::TimeForfeit,
+                    );
+                }
+                Err(EngineError::Crash) => {
+                    return finish(&gamestate, forfeit(seats.len(), player), Termination::Crash);
+                }
+            };
+            match reply.strip_prefix("bestmove").map(str::trim) {
+                Some("resign") => {
+                    return finish(
+                        &gamestate,
+                        forfeit(seats.len(), player),
+                        Termination::Resignation,
+                    );
+                }
+                // Only one offer may be made per turn, so a second is treated as an illegal move
+                Some("draw") if !draw_offers[player] => {
+                    draw_offers[player] = true;
+                    if draw_offers.iter().all(|&offered| offered) {
+                        return finish(
+                            &gamestate,
+                            GameResult::Draw((0..seats.len()).collect()),
+                            Termination::DrawAgreed,
+                        );
+                    }
+                }
+                _ => break reply,
+            }
+        };
+        let elapsed = start.elapsed();
+
+        // Moves which can't be parsed are just as illegal as moves which can't be played
+        let choice = reply
+            .strip_prefix("bestmove")
+            .map(str::trim)
+            .and_then(|m| parse_move(m).ok())
+            .filter(|choice| gamestate.is_legal(choice));
+        let Some(choice) = choice else {
+            eprintln!(
+                "Illegal move from {}: \"{}\" in position {} ({:08x})",
+                players[player],
+                reply,
+                gamestate.to_azul_fen().trim_end(),
+                gamestate.checksum()
+            );
+            return finish(
+                &gamestate,
+                forfeit(seats.len(), player),
+                Termination::IllegalMove,
+            );
+        };
+        gamestate.make_move(&choice).expect("Move was validated");
+        if engines[engine].timed() {
+            clocks[engine] = clocks[engine].after_move(elapsed);
+        }
+
+        if gamestate.round_over() {
+            gamestate.setup_next_round();
+            // The game can't continue if no tiles remain to be dealt
+            if gamestate.round_over() {
+                break;
+            }
+            if !gamestate.is_game_over()
+                && let Some(leader) = config
+                    .resign_score
+                    .and_then(|delta| insurmountable_leader(&gamestate, delta))
+            {
+                return finish(
+                    &gamestate,
+                    GameResult::Win(leader),
+                    Termination::ScoreAdjudicated,
+                );
+            }
+        }
+    }
+    finish(&gamestate, gamestate.result(), Termination::Normal)
+}
+
+/// Returns the player who leads every other player by at least `delta` points, if no other
+/// player can catch up. There is never a leader in a game of one player. A player can catch up unless the best score they could still achieve,
+/// as given by [`Board::max_achievable_score`](azul_movegen::Board::max_achievable_score), is below the leader's current score.
+pub fn insurmountable_leader(gamestate: &GameState, delta: usize) -> Option<usize> {
+    let boards = gamestate.boards();
+    if boards.len() < 2 {
+        return None;
+    }
+    let (leader, best) = boards
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, board)| board.get_score())?;
+    let lead = best.get_score();
+    boards
+        .iter()
+        .enumerate()
+        .filter(|&(player, _)| player != leader)
+        .all(|(_, board)| lead >= board.get_score() + delta && board.max_achievable_score() < lead)
+        .then_some(leader)
+}
+
+/// Returns the result of a game forfeited by the given player, shared by every other player.
+fn forfeit(players: usize, offender: usize) -> GameResult {
+    let winners: Vec<_> = (0..players).filter(|&p| p != offender).collect();
+    match winners.as_slice() {
+        [winner] => GameResult::Win(*winner),
+        _ => GameResult::Draw(winners),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    /// Plays the first legal move in every position, after waiting for the given delay.
+    struct FirstMove {
+        name: String,
+        delay: Duration,
+    }
+
+    impl FirstMove {
+        fn new(name: &str, delay: Duration) -> Self {
+            FirstMove {
+                name: name.to_string(),
+                delay,
+            }
+        }
+    }
+
+    impl Engine for FirstMove {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn go(&mut self, state: &GameState, _time: MoveTime) -> Result<String, EngineError> {
+            thread::sleep(self.delay);
+            Ok(format!("bestmove {}", state.get_valid_moves()[0]))
+        }
+    }
+
+    /// Gives each of the scripted replies in turn, and then plays the first legal move.
+    struct Scripted {
+        name: String,
+        replies: Vec<Result<String, EngineError>>,
+    }
+
+    impl Scripted {
+        fn new(name: &str, replies: &[Result<&str, EngineError>]) -> Self {
+            Scripted {
+                name: name.to_string(),
+                replies: replies
+                    .iter()
+                    .rev()
+                    .map(|reply| reply.map(String::from))
+                    .collect(),
+            }
+        }
+    }
+
+    impl Engine for Scripted {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn go(&mut self, state: &GameState, _time: MoveTime) -> Result<String, EngineError> {
+            self.replies
+                .pop()
+                .unwrap_or_else(|| Ok(format!("bestmove {}", state.get_valid_moves()[0])))
+        }
+    }
+
+    /// Plays the first legal move in every position, noting the active player and round each time
+    /// it is asked for a move.
+    struct Logged {
+        name: String,
+        asked: Vec<(usize, usize)>,
+    }
+
+    impl Logged {
+        fn new(name: &str) -> Self {
+            Logged {
+                name: name.to_string(),
+                asked: Vec::new(),
+            }
+        }
+    }
+
+    impl Engine for Logged {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn go(&mut self, state: &GameState, _time: MoveTime) -> Result<String, EngineError> {
+            self.asked.push((*state.active_player(), *state.round()));
+            Ok(format!("bestmove {}", state.get_valid_moves()[0]))
+        }
+    }
+
+    fn config(time_controls: Vec<Option<TimeControl>>) -> MatchConfig {
+        MatchConfig {
+            time_controls,
+            adjudicate_moves: None,
+            resign_score: None,
+        }
+    }
+
+    #[test]
+    fn timeouts_forfeit_with_a_valid_record() {
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = Scripted::new("b", &[Err(EngineError::Timeout)]);
+        let mut c = FirstMove::new("c", Duration::ZERO);
+        let record = play_game(
+            &mut [&mut a, &mut b, &mut c],
+            3,
+            &config(vec![None, None, None]),
+        );
+
+        assert_eq!(record.termination, Termination::TimeForfeit);
+        assert_eq!(record.result, GameResult::Draw(vec![0, 2]));
+        assert_eq!(record.players, ["a", "b", "c"]);
+        assert_eq!(record.scores.len(), 3);
+        assert_eq!(record.moves.len(), 1);
+        assert_eq!(record.seed, 3);
+        assert_eq!(record.rounds_played, 1);
+    }
+
+    #[test]
+    fn illegal_moves_forfeit() {
+        for reply in ["bestmove 990100", "bestmove nonsense", "move 010100"] {
+            let mut a = FirstMove::new("a", Duration::ZERO);
+            let mut b = Scripted::new("b", &[Ok(reply)]);
+            let record = play_game(&mut [&mut a, &mut b], 4, &config(vec![None, None]));
+            assert_eq!(record.termination, Termination::IllegalMove, "{}", reply);
+            assert_eq!(record.result, GameResult::Win(0));
+            assert_eq!(record.moves.len(), 1);
+        }
+    }
+
+    #[test]
+    fn move_cap_adjudicates() {
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = FirstMove::new("b", Duration::ZERO);
+        let record = play_game(
+            &mut [&mut a, &mut b],
+            5,
+            &MatchConfig {
+                adjudicate_moves: Some(2),
+                ..config(vec![None, None])
+            },
+        );
+        assert_eq!(record.termination, Termination::MoveLimit);
+        assert_eq!(record.moves.len(), 2);
+        assert_eq!(record.scores, [0, 0]);
+        assert_eq!(record.result, GameResult::Draw(vec![0, 1]));
+    }
+
+    #[test]
+    fn start_matches_time_control() {
+        assert_eq!(
+            MoveTime::start(Some(&TimeControl::Increment(300, 5))),
+            MoveTime::Clock {
+                remaining: Duration::from_secs(300),
+                increment: Duration::from_secs(5),
+            }
+        );
+        assert_eq!(
+            MoveTime::start(Some(&TimeControl::Fixed(10))),
+            MoveTime::Fixed(Duration::from_millis(10))
+        );
+    }
+
+    #[test]
+    fn clock_carries_time_between_moves() {
+        let fixed = MoveTime::Fixed(Duration::from_millis(10));
+        assert_eq!(fixed.after_move(Duration::from_millis(8)), fixed);
+
+        let clock = MoveTime::start(Some(&TimeControl::Increment(300, 5)));
+        assert_eq!(
+            clock.after_move(Duration::from_secs(10)).limit(),
+            Duration::from_secs(295)
+        );
+    }
+
+    #[test]
+    fn fixed_time_forfeits_slow_moves() {
+        let mut fast = FirstMove::new("fast", Duration::ZERO);
+        let mut slow = FirstMove::new("slow", Duration::from_millis(50));
+        let record = play_game(
+            &mut [&mut fast, &mut slow],
+            1,
+            &config(vec![None, Some(TimeControl::Fixed(10))]),
+        );
+        assert_eq!(record.termination, Termination::TimeForfeit);
+        assert_eq!(record.result, GameResult::Win(0));
+    }
+
+    #[test]
+    fn empty_clock_forfeits() {
+        let mut a = FirstMove::new("a", Duration::from_millis(5));
+        let mut b = FirstMove::new("b", Duration::ZERO);
+        let record = play_game(
+            &mut [&mut a, &mut b],
+            1,
+            &config(vec![Some(TimeControl::Increment(0, 0)), None]),
+        );
+        assert_eq!(record.termination, Termination::TimeForfeit);
+        assert_eq!(record.result, GameResult::Win(1));
+    }
+
+    #[test]
+    fn untimed_games_finish() {
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = FirstMove::new("b", Duration::ZERO);
+        let record = play_game(&mut [&mut a, &mut b], 2, &config(vec![None, None]));
+        assert_eq!(record.termination, Termination::Normal);
+        assert!(!record.moves.is_empty());
+    }
+
+    #[test]
+    fn records_count_every_round_set_up() {
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = FirstMove::new("b", Duration::ZERO);
+        let record = play_game(&mut [&mut a, &mut b], 6, &config(vec![None, None]));
+        assert_eq!(record.termination, Termination::Normal);
+
+        // Replaying the game sets up a round at the start and after each round played out
+        let mut gamestate = GameState::new_seeded(2, 6);
+        gamestate.setup_next_round();
+        let mut setups = 1;
+        for choice in &record.moves {
+            gamestate.make_move(choice).unwrap();
+            if gamestate.round_over() {
+                gamestate.setup_next_round();
+                setups += 1;
+            }
+        }
+        assert!(setups > 1);
+        assert_eq!(record.rounds_played, setups);
+    }
+
+    #[test]
+    fn seated_engines_alternate_across_rounds() {
+        let mut a = Logged::new("a");
+        let mut b = Logged::new("b");
+        let config = MatchConfig {
+            adjudicate_moves: Some(40),
+            ..config(vec![None, None])
+        };
+        // Engine b controls player 0, and engine a controls player 1
+        let record = play_game_seated(&mut [&mut a, &mut b], &[1, 0], 7, &config);
+        assert_eq!(record.players, ["b", "a"]);
+        assert!(a.asked.iter().all(|&(player, _)| player == 1));
+        assert!(b.asked.iter().all(|&(player, _)| player == 0));
+        assert_eq!(a.asked.len() + b.asked.len(), record.moves.len());
+
+        // Each engine was asked once per move of its player, and whoever took the first player
+        // token was the first asked in the second round
+        let mut gamestate = GameState::new_seeded(2, 7);
+        gamestate.setup_next_round();
+        let mut moves_by_player = [0, 0];
+        let mut token_owner = None;
+        for choice in &record.moves {
+            if let Some(owner) = token_owner.take() {
+                assert_eq!(*gamestate.active_player(), owner);
+            }
+            moves_by_player[*gamestate.active_player()] += 1;
+            gamestate.make_move(choice).unwrap();
+            if gamestate.round_over() {
+                token_owner = *gamestate.first_token_owner();
+                gamestate.setup_next_round();
+            }
+        }
+        assert_eq!(b.asked.len(), moves_by_player[0]);
+        assert_eq!(a.asked.len(), moves_by_player[1]);
+        assert!(a.asked.iter().chain(&b.asked).any(|&(_, round)| round == 2));
+    }
+
+    #[test]
+    fn resigning_loses_the_game() {
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = Scripted::new("b", &[Ok("bestmove resign")]);
+        let record = play_game(&mut [&mut a, &mut b], 8, &config(vec![None, None]));
+        assert_eq!(record.termination, Termination::Resignation);
+        assert_eq!(record.result, GameResult::Win(0));
+        assert_eq!(record.moves.len(), 1);
+    }
+
+    #[test]
+    fn mutual_draw_offers_draw_the_game() {
+        let mut a = Scripted::new("a", &[Ok("bestmove draw")]);
+        let mut b = Scripted::new("b", &[Ok("bestmove draw")]);
+        let record = play_game(&mut [&mut a, &mut b], 8, &config(vec![None, None]));
+        assert_eq!(record.termination, Termination::DrawAgreed);
+        assert_eq!(record.result, GameResult::Draw(vec![0, 1]));
+    }
+
+    #[test]
+    fn insurmountable_leads_are_adjudicated_at_round_boundaries() {
+        use azul_movegen::Board;
+
+        let mut dealt = GameState::new_seeded(2, 9);
+        dealt.setup_next_round();
+        let leader = Board::builder().score(400).build();
+        assert!(Board::default().max_achievable_score() < 400);
+        let start = GameState::builder()
+            .boards(vec![Board::default(), leader])
+            .bowls(dealt.bowls().clone())
+            .bag(dealt.bag().clone())
+            .seed(Some(9))
+            .round(1)
+            .try_build()
+            .unwrap();
+        assert_eq!(insurmountable_leader(&start, 50), Some(1));
+
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = FirstMove::new("b", Duration::ZERO);
+        let config = MatchConfig {
+            resign_score: Some(50),
+            ..config(vec![None, None])
+        };
+        let record = play_game_from(&mut [&mut a, &mut b], &[0, 1], start, &config);
+        assert_eq!(record.termination, Termination::ScoreAdjudicated);
+        assert_eq!(record.result, GameResult::Win(1));
+        assert_eq!(record.rounds_played, 2);
+        assert!(!record.moves.is_empty());
+    }
+
+    /// Notes every progress update it receives.
+    #[derive(Default)]
+    struct Recording {
+        updates: Vec<(usize, usize, usize)>,
+    }
+
+    impl ProgressReporter for Recording {
+        fn on_game_complete(&mut self, done: usize, total: usize, last: &GameRecord) {
+            self.updates.push((done, total, last.moves.len()));
+        }
+    }
+
+    #[test]
+    fn progress_is_reported_once_per_game() {
+        let starts: Vec<_> = (0..3)
+            .map(|seed| {
+                let mut start = GameState::new_seeded(2, seed);
+                start.setup_next_round();
+                start
+            })
+            .collect();
+        let mut a = FirstMove::new("a", Duration::ZERO);
+        let mut b = FirstMove::new("b", Duration::ZERO);
+        let mut reporter = Recording::default();
+        let config = MatchConfig {
+            adjudicate_moves: Some(4),
+            ..config(vec![None, None])
+        };
+        let records = play_games(
+            &mut [&mut a, &mut b],
+            &[0, 1],
+            starts,
+            &config,
+            &mut reporter,
+        );
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(reporter.updates, [(1, 3, 4), (2, 3, 4), (3, 3, 4)]);
+
+        let mut console = ConsoleProgress::new(Vec::new());
+        console.on_game_complete(1, 4, &records[0]);
+        let output = String::from_utf8(console.into_inner()).unwrap();
+        assert!(output.starts_with("[ 25%] 1/4 games, "));
+    }
+}
@@ -0,0 +1,548 @@
+use std::{collections::HashMap, io::Write};
+
+use azul_movegen::GameResult;
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{
+    protocol::{EngineConfig, TournamentStyle, engine_names},
+    record::GameRecord,
+};
+
+/// The outcome of a single game from the perspective of one engine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl Outcome {
+    /// Returns the same outcome from the perspective of the opponent.
+    pub fn reversed(&self) -> Outcome {
+        match self {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Draw => Outcome::Draw,
+            Outcome::Loss => Outcome::Win,
+        }
+    }
+}
+
+/// A count of wins, draws, and losses.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Tally {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Tally {
+    /// Adds the given outcome to this tally.
+    pub fn record(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+    }
+
+    /// Adds the counts of another tally to this tally.
+    pub fn add(&mut self, other: &Tally) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+
+    /// Returns the number of games in this tally.
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Returns the points scored, with one point per win and half a point per draw.
+    pub fn points(&self) -> f32 {
+        self.wins as f32 + self.draws as f32 * 0.5
+    }
+}
+
+impl std::fmt::Display for Tally {
+    /// Formats this tally as `W-D-L`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// The accumulated head-to-head results between the engines of a tournament.
+/// Results are kept per pairing of engines rather than per seat, so games played with swapped
+/// seats are combined into the same pairing.
+/// The final score of each engine in each of its games, and the number of rounds played in each
+/// game of a pairing, are also kept for averaging, as is the point margin of each game by the
+/// opening it was played from.
+#[derive(Debug, Clone, Default)]
+pub struct Results {
+    engines: Vec<String>,
+    pairings: HashMap<(usize, usize), Tally>,
+    score_totals: Vec<usize>,
+    scored_games: Vec<usize>,
+    pairing_rounds: HashMap<(usize, usize), (usize, usize)>,
+    opening_margins: HashMap<String, (i64, usize)>,
+}
+
+impl Results {
+    /// Creates an empty set of results between the engines with the given names.
+    pub fn new(engines: Vec<String>) -> Self {
+        Results {
+            score_totals: vec![0; engines.len()],
+            scored_games: vec![0; engines.len()],
+            engines,
+            pairings: HashMap::new(),
+            pairing_rounds: HashMap::new(),
+            opening_margins: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty set of results between the given engines, shown under their display
+    /// names. See [`engine_names`] for how engines sharing a name are told apart.
+    pub fn from_configs(configs: &[EngineConfig]) -> Self {
+        Results::new(engine_names(configs))
+    }
+
+    /// Getter for the names of the engines in these results.
+    pub fn engines(&self) -> &Vec<String> {
+        &self.engines
+    }
+
+    /// Records the outcome of a game between two engines, from the perspective of `engine`.
+    pub fn record(&mut self, engine: usize, opponent: usize, outcome: Outcome) {
+        self.pairings
+            .entry((engine, opponent))
+            .or_default()
+            .record(outcome);
+        self.pairings
+            .entry((opponent, engine))
+            .or_default()
+            .record(outcome.reversed());
+    }
+
+    /// Records the final score of an engine in a single game.
+    pub fn record_score(&mut self, engine: usize, score: usize) {
+        self.score_totals[engine] += score;
+        self.scored_games[engine] += 1;
+    }
+
+    /// Records the number of rounds played in a single game between two engines.
+    pub fn record_rounds(&mut self, engine: usize, opponent: usize, rounds: usize) {
+        for pairing in [(engine, opponent), (opponent, engine)] {
+            let (total, games) = self.pairing_rounds.entry(pairing).or_default();
+            *total += rounds;
+            *games += 1;
+        }
+    }
+
+    /// Records the point margin of a single game played from the opening with the given AzulFEN,
+    /// see [`GameRecord::point_margin`].
+    pub fn record_margin(&mut self, opening: &str, margin: i32) {
+        let (total, games) = self
+            .opening_margins
+            .entry(opening.trim_end().to_string())
+            .or_default();
+        *total += margin as i64;
+        *games += 1;
+    }
+
+    /// Records a completed game, given the engine seated in each seat of the game.
+    /// Every pair of engines in the game is recorded as a head-to-head result, where sharing the
+    /// victory, or both losing, counts as a draw, along with the number of rounds played.
+    /// Each engine's final score is recorded as well, as is the game's point margin under its
+    /// opening, see [`GameRecord::opening_fen`].
+    pub fn record_game(&mut self, seats: &[usize], record: &GameRecord) {
+        let won = |seat: usize| match &record.result {
+            GameResult::Win(winner) => *winner == seat,
+            GameResult::Draw(winners) => winners.contains(&seat),
+        };
+        for (seat, &engine) in seats.iter().enumerate() {
+            for (other, &opponent) in seats.iter().enumerate().skip(seat + 1) {
+                let outcome = match (won(seat), won(other)) {
+                    (true, false) => Outcome::Win,
+                    (false, true) => Outcome::Loss,
+                    _ => Outcome::Draw,
+                };
+                self.record(engine, opponent, outcome);
+                self.record_rounds(engine, opponent, record.rounds_played);
+            }
+            self.record_score(engine, record.scores[seat]);
+        }
+        self.record_margin(&record.opening_fen(), record.point_margin());
+    }
+
+    /// Returns the average final score of an engine across its recorded games, or zero if none
+    /// have been recorded.
+    pub fn average_score(&self, engine: usize) -> f32 {
+        match self.scored_games[engine] {
+            0 => 0.0,
+            games => self.score_totals[engine] as f32 / games as f32,
+        }
+    }
+
+    /// Returns the average number of rounds played in the recorded games between two engines, or
+    /// zero if none have been recorded.
+    pub fn average_rounds(&self, engine: usize, opponent: usize) -> f32 {
+        match self.pairing_rounds.get(&(engine, opponent)) {
+            Some(&(total, games)) if games > 0 => total as f32 / games as f32,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns the average point margin of the recorded games played from the opening with the
+    /// given AzulFEN, or `None` if none have been recorded.
+    pub fn average_margin(&self, opening: &str) -> Option<f32> {
+        match self.opening_margins.get(opening.trim_end()) {
+            Some(&(total, games)) if games > 0 => Some(total as f32 / games as f32),
+            _ => None,
+        }
+    }
+
+    /// Returns the AzulFEN of each opening with recorded games, along with its average point
+    /// margin and its number of games. Openings are ordered from the largest average margin to the
+    /// smallest, so the most lopsided openings come first and can be pruned from a book.
+    pub fn opening_margins(&self) -> Vec<(String, f32, usize)> {
+        let mut margins: Vec<_> = self
+            .opening_margins
+            .iter()
+            .map(|(opening, &(total, games))| (opening.clone(), total as f32 / games as f32, games))
+            .collect();
+        margins.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        margins
+    }
+
+    /// Returns the results of an engine against a single opponent.
+    pub fn head_to_head(&self, engine: usize, opponent: usize) -> Tally {
+        self.pairings
+            .get(&(engine, opponent))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the combined results of an engine against all of its opponents.
+    pub fn total(&self, engine: usize) -> Tally {
+        let mut total = Tally::default();
+        for opponent in 0..self.engines.len() {
+            if opponent != engine {
+                total.add(&self.head_to_head(engine, opponent));
+            }
+        }
+        total
+    }
+
+    /// Returns a one-line summary of the results of an engine against a single opponent so far,
+    /// in the form `A vs B: +W -L =D (after N games)`.
+    pub fn live_line(&self, engine: usize, opponent: usize) -> String {
+        let tally = self.head_to_head(engine, opponent);
+        format!(
+            "{} vs {}: +{} -{} ={} (after {} games)",
+            self.engines[engine],
+            self.engines[opponent],
+            tally.wins,
+            tally.losses,
+            tally.draws,
+            tally.games()
+        )
+    }
+
+    /// Renders a crosstable of these results, with engines on both axes and each cell holding the
+    /// `W-D-L` record of the row's engine against the column's engine.
+    /// Each row ends with the engine's total record and points.
+    pub fn crosstable(&self) -> String {
+        let totals: Vec<_> = (0..self.engines.len())
+            .map(|e| {
+                let total = self.total(e);
+                format!("{} ({})", total, total.points())
+            })
+            .collect();
+        let cells: Vec<Vec<_>> = (0..self.engines.len())
+            .map(|e| {
+                (0..self.engines.len())
+                    .map(|o| {
+                        if e == o {
+                            "-".to_string()
+                        } else {
+                            self.head_to_head(e, o).to_string()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let width = self
+            .engines
+            .iter()
+            .map(|e| e.len())
+            .chain(cells.iter().flatten().map(|c| c.len()))
+            .chain(totals.iter().map(|t| t.len()))
+            .chain(["engine".len(), "total".len()])
+            .max()
+            .unwrap_or_default();
+
+        let mut output = String::new();
+        let mut push_row = |first: &str, rest: &[String], total: &str| {
+            output.push_str(&format!("{:<width$}", first));
+            for cell in rest {
+                output.push_str(&format!(" | {:<width$}", cell));
+            }
+            output.push_str(&format!(" | {}\n", total));
+        };
+        push_row("engine", &self.engines, "total");
+        for ((engine, row), total) in self.engines.iter().zip(&cells).zip(&totals) {
+            push_row(engine, row, total);
+        }
+        output
+    }
+
+    /// Renders a summary of these results, with a line per engine holding its total `W-D-L`
+    /// record, its points, and its average final score. This is followed by a line per pairing of
+    /// engines which have played each other, holding the average number of rounds per game, and
+    /// then a line per opening holding its average point margin, see [`Results::opening_margins`].
+    pub fn summary(&self) -> String {
+        let mut output = String::new();
+        for (e, engine) in self.engines.iter().enumerate() {
+            let total = self.total(e);
+            output.push_str(&format!(
+                "{}: {} ({} points), average score {:.1}\n",
+                engine,
+                total,
+                total.points(),
+                self.average_score(e)
+            ));
+        }
+        for engine in 0..self.engines.len() {
+            for opponent in engine + 1..self.engines.len() {
+                if let Some(&(_, games)) = self.pairing_rounds.get(&(engine, opponent)) {
+                    output.push_str(&format!(
+                        "{} vs {}: average {:.1} rounds over {} games\n",
+                        self.engines[engine],
+                        self.engines[opponent],
+                        self.average_rounds(engine, opponent),
+                        games
+                    ));
+                }
+            }
+        }
+        for (opening, margin, games) in self.opening_margins() {
+            output.push_str(&format!(
+                "opening {}: average margin {:.1} over {} games\n",
+                opening, margin, games
+            ));
+        }
+        output
+    }
+}
+
+/// Returns the pairs of engines which play each other in the next round of a tournament of the
+/// given style, as indices into the engines of the results so far.
+/// * Gauntlet: the first engine plays every other engine.
+/// * Round robin: every engine plays every other engine.
+/// * Swiss: engines are ranked by their points so far, and each plays the next engine in the
+///   ranking. Engines with the same points keep the order they were given in. With an odd number
+///   of engines, the lowest ranked of the engines which have played the most games sits out.
+/// * Random: engines are shuffled using `rng`, and each plays the next engine. With an odd number
+///   of engines, the last engine after shuffling sits out.
+pub fn pairings(
+    style: TournamentStyle,
+    results: &Results,
+    rng: &mut impl Rng,
+) -> Vec<(usize, usize)> {
+    let engines = results.engines().len();
+    let mut order: Vec<_> = (0..engines).collect();
+    match style {
+        TournamentStyle::Gauntlet => return (1..engines).map(|opponent| (0, opponent)).collect(),
+        TournamentStyle::RoundRobin => {
+            return (0..engines)
+                .flat_map(|engine| (engine + 1..engines).map(move |opponent| (engine, opponent)))
+                .collect();
+        }
+        TournamentStyle::Swiss => {
+            order.sort_by(|&a, &b| {
+                results
+                    .total(b)
+                    .points()
+                    .total_cmp(&results.total(a).points())
+            });
+            // The lowest ranked of the engines which have played the most games sits out, so that
+            // every engine sits out in turn
+            let games = |engine: usize| results.total(engine).games();
+            if engines % 2 == 1
+                && let Some(most) = order.iter().map(|&e| games(e)).max()
+                && let Some(idx) = order.iter().rposition(|&e| games(e) == most)
+            {
+                order.remove(idx);
+            }
+        }
+        TournamentStyle::Random => order.shuffle(rng),
+    }
+    order
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect()
+}
+
+/// Prints a running head-to-head score line after each completed game, as live feedback while a
+/// tournament is in progress. This is separate from the final summary.
+pub struct LivePrinter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> LivePrinter<W> {
+    /// Creates a new printer which writes to the given output.
+    pub fn new(out: W) -> Self {
+        LivePrinter { out }
+    }
+
+    /// Prints the updated results between the two engines of a game which has just completed.
+    pub fn game_finished(
+        &mut self,
+        results: &Results,
+        engine: usize,
+        opponent: usize,
+    ) -> std::io::Result<()> {
+        writeln!(self.out, "{}", results.live_line(engine, opponent))
+    }
+
+    /// Consumes this printer, returning its output.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn crosstable_combines_pairings() {
+        let mut results = Results::new(names(&["a", "b", "c"]));
+        results.record(0, 1, Outcome::Win);
+        // The same pairing with the engines in swapped seats
+        results.record(1, 0, Outcome::Draw);
+        results.record(0, 2, Outcome::Loss);
+        results.record(2, 1, Outcome::Win);
+
+        let table = results.crosstable();
+        let rows: Vec<Vec<_>> = table
+            .lines()
+            .map(|line| line.split('|').map(str::trim).collect())
+            .collect();
+        assert_eq!(rows[0], ["engine", "a", "b", "c", "total"]);
+        assert_eq!(rows[1], ["a", "-", "1-1-0", "0-0-1", "1-1-1 (1.5)"]);
+        assert_eq!(rows[2], ["b", "0-1-1", "-", "0-0-1", "0-1-2 (0.5)"]);
+        assert_eq!(rows[3], ["c", "1-0-0", "1-0-0", "-", "2-0-0 (2)"]);
+    }
+
+    #[test]
+    fn round_robin_pairs_everyone() {
+        let results = Results::new(names(&["a", "b", "c"]));
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            pairings(TournamentStyle::RoundRobin, &results, &mut rng),
+            [(0, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(
+            pairings(TournamentStyle::Gauntlet, &results, &mut rng),
+            [(0, 1), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn swiss_pairs_by_points_and_rotates_the_bye() {
+        let mut results = Results::new(names(&["a", "b", "c"]));
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(
+            pairings(TournamentStyle::Swiss, &results, &mut rng),
+            [(0, 1)]
+        );
+
+        results.record(1, 0, Outcome::Win);
+        // Both a and b have played, so the lower ranked of them sits out
+        assert_eq!(
+            pairings(TournamentStyle::Swiss, &results, &mut rng),
+            [(1, 2)]
+        );
+    }
+
+    #[test]
+    fn random_pairs_each_engine_once() {
+        let results = Results::new(names(&["a", "b", "c", "d"]));
+        let mut rng = StdRng::seed_from_u64(5);
+        let pairs = pairings(TournamentStyle::Random, &results, &mut rng);
+        let mut engines: Vec<_> = pairs.iter().flat_map(|&(a, b)| [a, b]).collect();
+        engines.sort();
+        assert_eq!(engines, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn live_printer_tracks_running_score() {
+        let mut results = Results::new(names(&["A", "B"]));
+        let mut printer = LivePrinter::new(Vec::new());
+        for outcome in [Outcome::Win, Outcome::Loss, Outcome::Win] {
+            results.record(0, 1, outcome);
+            printer.game_finished(&results, 0, 1).unwrap();
+        }
+        let output = String::from_utf8(printer.into_inner()).unwrap();
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            [
+                "A vs B: +1 -0 =0 (after 1 games)",
+                "A vs B: +1 -1 =0 (after 2 games)",
+                "A vs B: +2 -1 =0 (after 3 games)",
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_averages_final_scores() {
+        let mut results = Results::new(names(&["a", "b"]));
+        for (a, b) in [(40, 25), (31, 36), (50, 20)] {
+            results.record_score(0, a);
+            results.record_score(1, b);
+            let outcome = if a > b { Outcome::Win } else { Outcome::Loss };
+            results.record(0, 1, outcome);
+        }
+        assert_eq!(results.average_score(0), 121. / 3.);
+        assert_eq!(results.average_score(1), 27.);
+
+        let summary = results.summary();
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(lines[0], "a: 2-0-1 (2 points), average score 40.3");
+        assert_eq!(lines[1], "b: 1-0-2 (1 points), average score 27.0");
+    }
+
+    #[test]
+    fn opening_margins_put_lopsided_openings_first() {
+        let mut results = Results::new(names(&["a", "b"]));
+        for (opening, margin) in [("even", 2), ("lopsided", 30), ("even", 4), ("lopsided", 20)] {
+            results.record_margin(opening, margin);
+        }
+        assert_eq!(results.average_margin("even"), Some(3.));
+        assert_eq!(results.average_margin("unplayed"), None);
+        assert_eq!(
+            results.opening_margins(),
+            [
+                ("lopsided".to_string(), 25., 2),
+                ("even".to_string(), 3., 2)
+            ]
+        );
+
+        let summary = results.summary();
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(
+            lines[2..],
+            [
+                "opening lopsided: average margin 25.0 over 2 games",
+                "opening even: average margin 3.0 over 2 games",
+            ]
+        );
+    }
+}
@@ -1,32 +1,128 @@
-use rand::{rng, seq::SliceRandom};
+use std::{fmt::Display, str::FromStr};
+
+use rand::{SeedableRng, rng, rngs::StdRng, seq::SliceRandom};
+
+use crate::{Tile, board::TILE_TYPES, fen::ParseFenError};
 
 /// This struct is for handling a shuffled `Vec<T>` of items.
 /// Items are removed from the bag when accessed and bags may be restocked at any time.
-#[derive(Debug, Default)]
+/// Each bag owns the random number generator used for its shuffles, so a seeded bag shuffles
+/// identically across runs.
+#[derive(Debug, Clone)]
 pub struct Bag<T> {
     items: Vec<T>,
+    rng: StdRng,
+}
+
+impl<T> Default for Bag<T> {
+    fn default() -> Self {
+        Bag {
+            items: Vec::new(),
+            rng: StdRng::from_rng(&mut rng()),
+        }
+    }
 }
 
 impl<T> Bag<T> {
     /// Creates a new bag from `items` after shuffling them.
-    pub fn new(mut items: Vec<T>) -> Self {
-        items.shuffle(&mut rng());
-        Bag { items }
+    pub fn new(items: Vec<T>) -> Self {
+        let mut bag = Bag::default();
+        bag.restock(items);
+        bag
+    }
+
+    /// Creates a new bag from `items` after shuffling them, using the given seed for this
+    /// and all future shuffles.
+    pub fn new_seeded(items: Vec<T>, seed: u64) -> Self {
+        let mut bag = Bag {
+            items: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+        };
+        bag.restock(items);
+        bag
+    }
+
+    /// Creates a new bag from `items` without shuffling them, so that items are drawn in the
+    /// given order. Later restocks are still shuffled.
+    pub fn new_ordered(mut items: Vec<T>) -> Self {
+        // Items are drawn from the end
+        items.reverse();
+        Bag {
+            items,
+            ..Bag::default()
+        }
+    }
+
+    /// Reseeds the random number generator used for future shuffles.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 
     /// Restocks the bag with the given `items` after shuffling them.  
     /// Items previously in this bag are not retained.
     pub fn restock(&mut self, mut items: Vec<T>) {
-        items.shuffle(&mut rng());
+        items.shuffle(&mut self.rng);
         self.items = items;
     }
 
+    /// Restocks the bag with the given `items` after shuffling them, reusing this bag's
+    /// existing allocation.  
+    /// Items previously in this bag are not retained.
+    pub fn refill(&mut self, items: impl IntoIterator<Item = T>) {
+        self.items.clear();
+        self.items.extend(items);
+        self.items.shuffle(&mut self.rng);
+    }
+
+    /// Draws up to `n` items from this bag, in the same order as they would be drawn one at a time.
+    /// Fewer items are returned if the bag runs out.
+    pub fn draw(&mut self, n: usize) -> Vec<T> {
+        self.by_ref().take(n).collect()
+    }
+
     /// Getter for the items in this bag.
     pub fn items(&self) -> &Vec<T> {
         &self.items
     }
 }
 
+impl Bag<Tile> {
+    /// Counts the tiles of each type currently in this bag, indexed by tile type.
+    /// Tiles which are not a valid tile type are not counted.
+    pub fn histogram(&self) -> [usize; TILE_TYPES] {
+        let mut histogram = [0; TILE_TYPES];
+        for &tile in self.items.iter() {
+            if let Some(count) = histogram.get_mut(tile) {
+                *count += 1;
+            }
+        }
+        histogram
+    }
+}
+
+impl<T> Bag<T>
+where
+    T: Display + FromStr,
+{
+    /// Creates a bag from the given AzulFEN bag component, where each character is an item.
+    /// Unlike [`Bag::new`], the items are not shuffled, so the encoded draw order is retained.
+    pub fn from_fen(fen: &str) -> Result<Self, ParseFenError> {
+        let items = fen
+            .chars()
+            .map(|c| c.to_string().parse::<T>().or(Err(ParseFenError)))
+            .collect::<Result<Vec<_>, ParseFenError>>()?;
+        Ok(Bag {
+            items,
+            ..Bag::default()
+        })
+    }
+
+    /// Returns the AzulFEN bag component for this bag.
+    pub fn to_fen(&self) -> String {
+        self.items.iter().map(|t| t.to_string()).collect()
+    }
+}
+
 impl<T> Iterator for Bag<T> {
     type Item = T;
 
@@ -34,3 +130,36 @@ impl<T> Iterator for Bag<T> {
         self.items.pop()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Tile;
+
+    #[test]
+    fn fen_round_trips_in_draw_order() {
+        let bag: Bag<Tile> = Bag::new(vec![0, 0, 1, 2, 3, 4, 4]);
+        let fen = bag.to_fen();
+
+        let mut parsed = Bag::<Tile>::from_fen(&fen).unwrap();
+        assert_eq!(parsed.items(), bag.items());
+        assert_eq!(parsed.to_fen(), fen);
+        assert!(parsed.by_ref().eq(bag.clone()));
+        assert!(Bag::<Tile>::from_fen("01x").is_err());
+    }
+
+    #[test]
+    fn overdrawing_empties_the_bag() {
+        let mut bag = Bag::new_ordered(vec![0, 1, 2]);
+        assert_eq!(bag.draw(5).len(), 3);
+        assert!(bag.items().is_empty());
+        assert!(bag.draw(1).is_empty());
+    }
+
+    #[test]
+    fn histogram_counts_each_tile_type() {
+        let bag = Bag::new_seeded(vec![0, 0, 0, 2, 4, 4, 1], 3);
+        assert_eq!(bag.histogram(), [3, 1, 1, 0, 2]);
+        assert_eq!(Bag::<Tile>::default().histogram(), [0; TILE_TYPES]);
+    }
+}
@@ -0,0 +1,64 @@
+use std::time::{Duration, Instant};
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::GameState;
+
+/// The aggregate results of a benchmark run.
+/// # Properties
+/// * `games`: the number of games played to completion.
+/// * `total_moves`: the total number of moves played across all games.
+/// * `elapsed`: the wall-clock time taken to play every game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchResult {
+    pub games: usize,
+    pub total_moves: usize,
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    /// Returns the number of moves played per second, or 0 if no time has elapsed.
+    pub fn moves_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0. {
+            return 0.;
+        }
+        self.total_moves as f64 / secs
+    }
+}
+
+/// Self-plays the given number of games with the given number of players, choosing moves
+/// uniformly at random, and reports the aggregate throughput.
+///
+/// Each game is seeded from the given seed and its index, so the same moves are played on every
+/// run and only the elapsed time varies.
+pub fn run_games(players: usize, count: usize, seed: u64) -> BenchResult {
+    let start = Instant::now();
+    let mut total_moves = 0;
+    for game in 0..count {
+        let game_seed = seed.wrapping_add(game as u64);
+        let mut state = GameState::new_seeded(players, game_seed);
+        let mut rng = StdRng::seed_from_u64(game_seed);
+        state.random_playout(&mut rng);
+        total_moves += state.move_count();
+    }
+    BenchResult {
+        games: count,
+        total_moves,
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_every_game() {
+        let result = run_games(2, 3, 1);
+        assert_eq!(result.games, 3);
+        assert!(result.total_moves > 0);
+        assert!(result.moves_per_second() > 0.);
+        assert_eq!(run_games(2, 3, 1).total_moves, result.total_moves);
+    }
+}
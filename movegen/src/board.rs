@@ -1,9 +1,19 @@
-use crate::{Tile, game_move::IllegalMoveError, row::Row};
+use crate::{EvalWeights, Tile, game_move::IllegalMoveReason, row::Row, rules::END_ON_LINES};
 
 /// The width and height of the place area of the board. A single constant is used as
 /// all boards must be a square.
 pub const BOARD_DIMENSION: usize = 5;
 
+/// The number of tile types. Every wall row and column holds exactly one tile of each type,
+/// so the number of tile types always follows the board dimension.
+pub const TILE_TYPES: usize = BOARD_DIMENSION;
+
+/// Returns an iterator over every tile type, in ascending order. Tile types are always the
+/// indices `0..TILE_TYPES`, whatever tiles happen to remain in play.
+pub fn tile_types() -> impl Iterator<Item = Tile> + Clone {
+    0..TILE_TYPES
+}
+
 /// The score bonus given when a board row has been completely filled.
 const ROW_BONUS: usize = 2;
 
@@ -13,8 +23,19 @@ const COLUMN_BONUS: usize = 7;
 /// The score bonus given when all boardspaces for a given tile type have been filled.
 const TILE_TYPE_BONUS: usize = 10;
 
+/// The points lost for each slot of the floor, in order, so index `i` is the marginal point cost
+/// of the `(i + 1)`-th penalty tile. Penalty tiles beyond the last slot are not scored.
+pub const FLOOR_PENALTIES: [usize; 7] = [1, 1, 2, 2, 2, 3, 3];
+
+#[cfg(test)]
+thread_local! {
+    /// Counts the line walks made by [`Board::count_in_direction`] on this thread, so tests can
+    /// check which paths avoid them.
+    static LINE_WALKS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
 /// A player's board.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Board {
     holds: [[Option<Tile>; BOARD_DIMENSION]; BOARD_DIMENSION],
     placed: [[Option<Tile>; BOARD_DIMENSION]; BOARD_DIMENSION],
@@ -33,10 +54,18 @@ impl Board {
         holds: [[Option<Tile>; BOARD_DIMENSION]; BOARD_DIMENSION],
         placed: [[Option<Tile>; BOARD_DIMENSION]; BOARD_DIMENSION],
         bonuses: BonusTypes,
+        /// Getter for the number of penalty tiles on this board's floor, which are scored at the
+        /// end of the round. This counts tiles, not points, see [`Board::pending_penalty_points`].
         penalties: usize,
         score: usize,
     }
 
+    /// Resets this board to its state at the beginning of a game, clearing all held and placed
+    /// tiles, bonuses, penalties, and score.
+    pub fn reset(&mut self) {
+        *self = Board::default();
+    }
+
     /// Returns an iterator over all tiles on this board.
     /// Includes both the held and placed tiles.
     pub fn get_active_tiles(&self) -> impl Iterator<Item = Tile> + '_ {
@@ -49,33 +78,67 @@ impl Board {
 
     /// Returns a vec of all rows which do not yet contain the given tile type, both within
     /// the held and placed positions.
+    /// Wall rows are listed in ascending order, followed by the floor.
     pub fn get_valid_rows_for_tile_type(&self, tile_type: Tile) -> Vec<Row> {
-        let mut valid_rows = Vec::new();
-        for (row_idx, hold) in self.holds.iter().enumerate() {
-            // If we have a different tile held in this row
-            if hold.iter().any(|t| t.is_some_and(|x| x != tile_type)) {
-                continue;
-            }
-            // Or if we have this type of tile already placed somewhere in this row
-            if self
-                .placed
-                .get(row_idx)
-                .expect("Invalid row")
-                .get(Board::get_tile_place_col(tile_type, row_idx))
-                .expect("Invalid columnn")
-                .is_some_and(|t| t == tile_type)
-            {
-                continue;
-            }
-            valid_rows.push(Row::Wall(row_idx));
-        }
+        let mut valid_rows: Vec<_> = (0..BOARD_DIMENSION)
+            .map(Row::Wall)
+            .filter(|&row| self.can_hold(tile_type, row))
+            .collect();
         // We can always soak a penalty if we want
         valid_rows.push(Row::Floor);
         valid_rows
     }
 
+    /// Returns the same rows as [`Board::get_valid_rows_for_tile_type`], each paired with the
+    /// number of tiles it can still hold before any further tiles overflow to the floor.
+    /// The floor can accept any number of tiles, so its capacity is given as `usize::MAX`.
+    pub fn valid_rows_with_capacity(&self, tile_type: Tile) -> Vec<(Row, usize)> {
+        self.get_valid_rows_for_tile_type(tile_type)
+            .into_iter()
+            .map(|row| match row {
+                Row::Floor => (row, usize::MAX),
+                Row::Wall(idx) => (row, idx + 1 - self.holds[idx].iter().flatten().count()),
+            })
+            .collect()
+    }
+
+    /// Checks whether tiles of the given type may be held in the given row.
+    /// The floor is always a valid row. Will error with the reason if the row is not valid.
+    pub fn check_hold(&self, tile_type: Tile, row: Row) -> Result<(), IllegalMoveReason> {
+        let row_idx = match row {
+            Row::Floor => return Ok(()),
+            Row::Wall(idx) => idx,
+        };
+
+        // If we have a different tile held in this row
+        let hold = self
+            .holds
+            .get(row_idx)
+            .ok_or(IllegalMoveReason::NoSuchRow)?;
+        if hold.iter().any(|t| t.is_some_and(|x| x != tile_type)) {
+            return Err(IllegalMoveReason::RowColorConflict);
+        }
+
+        // Or if we have this type of tile already placed somewhere in this row
+        if self.placed[row_idx][Board::get_tile_place_col(tile_type, row_idx)]
+            .is_some_and(|t| t == tile_type)
+        {
+            return Err(IllegalMoveReason::RowAlreadyHasColorPlaced);
+        }
+        Ok(())
+    }
+
+    /// Returns true if tiles of the given type may be held in the given row, as checked by
+    /// [`Board::check_hold`]. The floor is always a valid row.
+    pub fn can_hold(&self, tile_type: Tile, row: Row) -> bool {
+        self.check_hold(tile_type, row).is_ok()
+    }
+
     /// Adds the given count of tiles of the given type to the hold positions at the given row index.
     /// Also accepts a penalty to apply to this board.
+    /// Will error without modifying this board if the tiles may not be held in the given row.
+    /// Returns the number of tiles which were added to the row, with any remaining tiles having
+    /// overflowed to the floor.
     /// ## Notes:
     /// - The penalty should only include special cases such as accepting the central tile, and not
     ///   cases such as overflow, which are handled by this method.
@@ -86,39 +149,34 @@ impl Board {
         tile_count: usize,
         row_idx: Row,
         penalty: usize,
-    ) -> Result<(), IllegalMoveError> {
+    ) -> Result<usize, IllegalMoveReason> {
+        // Validate the row, including existing tiles in that row and tiles placed on the wall
+        self.check_hold(tile_type, row_idx)?;
+
+        // We'll also deduct points in certain cases like if we took from the centre first
+        self.penalties += penalty;
+
         // If we wanted to put the tiles straight to the floor we'll just soak the penalty
         let row_idx = match row_idx {
             Row::Floor => {
                 self.penalties += tile_count;
-                return Ok(());
+                return Ok(0);
             }
             Row::Wall(idx) => idx,
         };
+        let row = &mut self.holds[row_idx];
 
-        // Validate row and existing tiles in that row
-        let row = self.holds.get_mut(row_idx).ok_or(IllegalMoveError)?;
-        if let Some(t) = row.first().unwrap()
-            && *t != tile_type
-        {
-            return Err(IllegalMoveError);
-        }
-
-        // Add tiles to that row, overflowing extra to the penalty section
+        // Add tiles to the free space in that row, overflowing extra to the penalty section
+        // Rows can only be overfull if built that way, in which case everything overflows
         let row_capacity = row_idx + 1;
-        for row in row.iter_mut().take(tile_count.min(row_capacity)) {
+        let free_space = row_capacity.saturating_sub(row.iter().flatten().count());
+        let held = tile_count.min(free_space);
+        for row in row.iter_mut().filter(|t| t.is_none()).take(held) {
             *row = Some(tile_type);
         }
+        self.penalties += tile_count - held;
 
-        let overflow = tile_count.saturating_sub(row_capacity);
-        for _ in 0..overflow {
-            self.penalties += 1;
-        }
-
-        // We'll also deduct points in certain cases like if we took from the centre first
-        self.penalties += penalty;
-
-        Ok(())
+        Ok(held)
     }
 
     /// Handles all end-of-round actions for this board, including:
@@ -126,124 +184,320 @@ impl Board {
     /// - Adding appropriate tiles to the placed positions
     /// - Ordinary tile scoring
     /// - Bonus scoring and tracking collected bonuses
-    /// - Penalty application and penalty resets
-    pub fn place_holds(&mut self) {
+    /// - Penalty application and penalty resets, see [`Board::apply_penalties`]
+    ///
+    /// Returns a report of everything scored, including the leftover tiles from each completed
+    /// held row, which are not placed and instead leave play to the discard.
+    pub fn place_holds(&mut self) -> ScoreReport {
+        let score_before = self.score;
+        let mut report = ScoreReport::default();
         for (row_idx, row) in self.holds.iter_mut().enumerate() {
             let tiles_in_row = row.iter().filter(|tile| tile.is_some()).count();
 
-            // We have enough tiles to place in this row
-            if tiles_in_row > row_idx {
-                // Let's determine the position
-                let tile_type = row[0].unwrap();
-                let col_idx = Board::get_tile_place_col(tile_type, row_idx);
-                *self
-                    .placed
-                    .get_mut(row_idx)
-                    .expect("Invalid row")
-                    .get_mut(col_idx)
-                    .expect("Invalid column") = Some(tile_type);
-
-                // Score newly placed tile
-                // We'll walk horizontal and vertically, counting the lengths of each group
-                let h_line =
-                    1 + Board::count_in_direction(
-                        &self.placed,
-                        row_idx as isize,
-                        col_idx as isize,
-                        0,
-                        1,
-                    ) + Board::count_in_direction(
-                        &self.placed,
-                        row_idx as isize,
-                        col_idx as isize,
-                        0,
-                        -1,
-                    );
-                let v_line =
-                    1 + Board::count_in_direction(
-                        &self.placed,
-                        row_idx as isize,
-                        col_idx as isize,
-                        1,
-                        0,
-                    ) + Board::count_in_direction(
-                        &self.placed,
-                        row_idx as isize,
-                        col_idx as isize,
-                        -1,
-                        0,
-                    );
-
-                // If the tile is alone, don't double-count it
-                self.score += if h_line == 1 && v_line == 1 {
-                    1
-                } else {
-                    // Otherwise, we count the score for axes with more tiles than one
-                    (if h_line > 1 { h_line } else { 0 }) + (if v_line > 1 { v_line } else { 0 })
-                };
-
-                // Now we'll clear the hold for this row
-                for tile in row.iter_mut() {
-                    *tile = None;
-                }
+            // Rows without enough tiles to place can't score, so skip them before any line walks
+            if tiles_in_row <= row_idx {
+                continue;
+            }
+
+            // Let's determine the position
+            let tile_type = row[0].unwrap();
+            let col_idx = Board::get_tile_place_col(tile_type, row_idx);
+            *self
+                .placed
+                .get_mut(row_idx)
+                .expect("Invalid row")
+                .get_mut(col_idx)
+                .expect("Invalid column") = Some(tile_type);
+
+            // Score newly placed tile
+            let points = Board::points_at(&self.placed, row_idx, col_idx);
+            self.score += points;
+            report.placement_points += points;
+            report.placed.push((row_idx, col_idx));
+
+            // Now we'll clear the hold for this row, discarding all but the placed tile
+            for tile in row.iter_mut() {
+                *tile = None;
             }
+            report
+                .discarded
+                .extend(std::iter::repeat_n(tile_type, row_idx));
         }
 
         // Let's apply bonuses that we haven't collected yet
-        self.apply_uncollected_bonuses();
+        report.bonuses = self.apply_uncollected_bonuses();
 
         // Let's also apply our penalties
-        self.score = self
-            .score
-            .saturating_sub(Board::get_penalty_point_value(self.penalties));
+        report.penalty_points = self.apply_penalties();
+        report.score_change = self.score as isize - score_before as isize;
+        report
+    }
+
+    /// Applies the penalty phase of scoring, deducting the points lost to the tiles on the floor
+    /// and clearing the floor. The score is limited to zero.
+    /// Called by [`Board::place_holds`] after placement, but may be called alone to show the
+    /// penalties as a separate step.
+    ///
+    /// Returns the points lost to penalties, before the score is limited to zero.
+    pub fn apply_penalties(&mut self) -> usize {
+        let points = Board::get_penalty_point_value(self.penalties);
+        self.score = self.score.saturating_sub(points);
         self.penalties = 0;
+        points
     }
 
-    /// Grants this board score for each bonus it satisfies that has not yet been collected,
-    /// then marks such bonuses as collected.
-    fn apply_uncollected_bonuses(&mut self) {
-        // Start with rows
-        for (i, claimed) in self.bonuses.rows.iter_mut().enumerate() {
-            if *claimed {
-                continue;
+    /// Clears every hold on this board, returning the tiles which were held.
+    /// Called after [`Board::place_holds`], this clears only the incomplete holds, for variants
+    /// where incomplete holds are not carried into the next round.
+    pub fn clear_holds(&mut self) -> Vec<Tile> {
+        let mut cleared = Vec::new();
+        for tile in self.holds.iter_mut().flatten() {
+            if let Some(t) = tile.take() {
+                cleared.push(t);
             }
-            // We haven't collected this bonus yet but this row has been filled,
-            // so we'll collect that
-            if self.placed[i].iter().all(|x| x.is_some()) {
-                self.score += ROW_BONUS;
-                *claimed = true;
+        }
+        cleared
+    }
+
+    /// Returns each bonus this board satisfies with its placed tiles but has not yet collected.
+    /// This does not grant or mark any bonuses as collected.
+    /// Bonuses are listed in a stable order which may be relied upon: rows, then columns, then
+    /// tile types, each by ascending index. This is also the order given by [`BonusEvent`]'s `Ord`.
+    pub fn collectible_bonuses(&self) -> Vec<BonusEvent> {
+        let mut events = Vec::new();
+
+        // Start with rows
+        for (i, claimed) in self.bonuses.rows.iter().enumerate() {
+            if !claimed && self.placed[i].iter().all(|x| x.is_some()) {
+                events.push(BonusEvent::Row(i));
             }
         }
 
         // Then columns
-        for (i, claimed) in self.bonuses.columns.iter_mut().enumerate() {
-            if *claimed {
-                continue;
-            }
-            if self.placed.iter().all(|row| row[i].is_some()) {
-                self.score += COLUMN_BONUS;
-                *claimed = true;
+        for (i, claimed) in self.bonuses.columns.iter().enumerate() {
+            if !claimed && self.placed.iter().all(|row| row[i].is_some()) {
+                events.push(BonusEvent::Column(i));
             }
         }
 
         // And finally, tile types
-        for (i, claimed) in self.bonuses.tile_types.iter_mut().enumerate() {
-            if *claimed {
-                continue;
+        let color_counts = self.placed_color_counts();
+        for (i, claimed) in self.bonuses.tile_types.iter().enumerate() {
+            if !claimed && color_counts[i] == BOARD_DIMENSION {
+                events.push(BonusEvent::Color(i));
             }
-            if self
-                .placed
+        }
+        events
+    }
+
+    /// Returns how many tiles of each type have been placed on the wall of this board, indexed by
+    /// tile type. A tile type's bonus is collected once all [`BOARD_DIMENSION`] are placed.
+    pub fn placed_color_counts(&self) -> [usize; TILE_TYPES] {
+        let mut counts = [0; TILE_TYPES];
+        for &tile_type in self.placed.iter().flatten().flatten() {
+            if let Some(count) = counts.get_mut(tile_type) {
+                *count += 1;
+            }
+        }
+        counts
+    }
+
+    /// Returns how many more tiles must be placed to complete each row, column, and tile type on
+    /// this board. Completed ones have a gap of zero, whether or not their bonus was collected.
+    pub fn completion_gaps(&self) -> CompletionGaps {
+        let mut gaps = CompletionGaps {
+            rows: [BOARD_DIMENSION; BOARD_DIMENSION],
+            columns: [BOARD_DIMENSION; BOARD_DIMENSION],
+            colors: [BOARD_DIMENSION; TILE_TYPES],
+        };
+        for (row, placed) in self.placed.iter().enumerate() {
+            for (col, tile) in placed.iter().enumerate() {
+                if tile.is_some() {
+                    gaps.rows[row] -= 1;
+                    gaps.columns[col] -= 1;
+                    // Each position only ever holds one tile type, whatever the stored tile says
+                    gaps.colors[Board::get_tile_type_at_pos(row, col)] -= 1;
+                }
+            }
+        }
+        gaps
+    }
+
+    /// Grants this board score for each bonus it satisfies that has not yet been collected,
+    /// then marks such bonuses as collected. Returns the bonuses which were collected.
+    fn apply_uncollected_bonuses(&mut self) -> Vec<BonusEvent> {
+        let events = self.collectible_bonuses();
+        for &event in events.iter() {
+            match event {
+                BonusEvent::Row(i) => {
+                    self.score += ROW_BONUS;
+                    self.bonuses.rows[i] = true;
+                }
+                BonusEvent::Column(i) => {
+                    self.score += COLUMN_BONUS;
+                    self.bonuses.columns[i] = true;
+                }
+                BonusEvent::Color(t) => {
+                    self.score += TILE_TYPE_BONUS;
+                    self.bonuses.tile_types[t] = true;
+                }
+            }
+        }
+        events
+    }
+
+    /// Returns a heuristic evaluation of this board, weighting each feature by the given weights.
+    ///
+    /// The evaluation starts from the current score, adding the weighted points its completed
+    /// holds would score if they were placed now, including any bonuses they complete. Then the
+    /// weighted progress towards each uncollected bonus is added, while the weighted penalty points
+    /// and stranded held tiles are subtracted. Progress and stranded tiles are counted as they
+    /// would be after placement. With all weights set to zero, only the current score remains,
+    /// as given by [`Board::get_score`].
+    pub fn evaluate(&self, weights: &EvalWeights) -> f32 {
+        // Penalties are weighted separately, so they're removed before placement
+        let mut board = *self;
+        let penalty_points = Board::get_penalty_point_value(board.penalties) as f32;
+        board.penalties = 0;
+        board.place_holds();
+
+        let progress = |claimed: &[bool], placed: &dyn Fn(usize) -> usize, bonus: usize| -> f32 {
+            claimed
                 .iter()
-                .flatten()
-                .filter_map(|&t| t)
-                .filter(|&t| t == i)
-                .count()
-                == BOARD_DIMENSION
-            {
-                self.score += TILE_TYPE_BONUS;
-                *claimed = true;
+                .enumerate()
+                .filter(|(_, c)| !**c)
+                .map(|(i, _)| placed(i) as f32 / BOARD_DIMENSION as f32 * bonus as f32)
+                .sum()
+        };
+        let row_potential = progress(
+            &board.bonuses.rows,
+            &|i| board.placed[i].iter().flatten().count(),
+            ROW_BONUS,
+        );
+        let column_potential = progress(
+            &board.bonuses.columns,
+            &|i| board.placed.iter().filter(|row| row[i].is_some()).count(),
+            COLUMN_BONUS,
+        );
+        let color_counts = board.placed_color_counts();
+        let color_potential = progress(
+            &board.bonuses.tile_types,
+            &|i| color_counts[i],
+            TILE_TYPE_BONUS,
+        );
+        let stranded = board.holds.iter().flatten().flatten().count() as f32;
+
+        let placement = (board.score - self.score) as f32;
+
+        self.score as f32
+            + weights.placement * placement
+            + weights.row_potential * row_potential
+            + weights.column_potential * column_potential
+            + weights.color_potential * color_potential
+            - weights.floor_penalty * penalty_points
+            - weights.held_stranded_penalty * stranded
+    }
+
+    /// Returns an optimistic upper bound on the final score this board could achieve.
+    ///
+    /// The bound is the current score plus every uncollected bonus, plus the best case adjacency
+    /// score for each empty wall position, where every placement completes both its row and column.
+    /// Tile availability and penalties are ignored, so this is an upper bound and not a guarantee.
+    pub fn max_achievable_score(&self) -> usize {
+        let uncollected = |claimed: &[bool], bonus: usize| -> usize {
+            claimed.iter().filter(|&&c| !c).count() * bonus
+        };
+        let empty_cells = self.placed.iter().flatten().filter(|t| t.is_none()).count();
+        self.score
+            + uncollected(&self.bonuses.rows, ROW_BONUS)
+            + uncollected(&self.bonuses.columns, COLUMN_BONUS)
+            + uncollected(&self.bonuses.tile_types, TILE_TYPE_BONUS)
+            + empty_cells * BOARD_DIMENSION * 2
+    }
+
+    /// Checks that this board is consistent with the rules of the game, which should always hold
+    /// for boards reached through play. Useful for debugging and for validating constructed boards.
+    /// Will error with the first broken invariant found.
+    pub fn is_valid(&self) -> Result<(), BoardInvariantError> {
+        for (row, placed) in self.placed.iter().enumerate() {
+            for (col, tile) in placed.iter().enumerate() {
+                if tile.is_some_and(|t| t != Board::get_tile_type_at_pos(row, col)) {
+                    return Err(BoardInvariantError::MisplacedTile { row, col });
+                }
             }
         }
+        for (row, hold) in self.holds.iter().enumerate() {
+            if hold.iter().skip(row + 1).any(|t| t.is_some()) {
+                return Err(BoardInvariantError::HoldOverCapacity { row });
+            }
+            let mut held = hold.iter().flatten();
+            let Some(&tile_type) = held.next() else {
+                continue;
+            };
+            if held.any(|&t| t != tile_type) {
+                return Err(BoardInvariantError::MixedHold { row });
+            }
+            if self.placed[row].contains(&Some(tile_type)) {
+                return Err(BoardInvariantError::HeldColorAlreadyPlaced { row });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the points this board will lose at the end of the round for the penalty tiles
+    /// currently on its floor, as given by [`FLOOR_PENALTIES`].
+    pub fn pending_penalty_points(&self) -> usize {
+        Board::get_penalty_point_value(self.penalties)
+    }
+
+    /// Returns the penalty points this board would lose at the end of the round if the given
+    /// number of additional tiles were dropped to its floor, on top of its current penalties.
+    /// The floor only holds so many tiles, so penalties beyond it add no further cost.
+    pub fn penalty_preview(&self, additional_floor_tiles: usize) -> usize {
+        Board::get_penalty_point_value(self.penalties + additional_floor_tiles)
+    }
+
+    /// Returns a compact, stable, single-line description of this board, suited to snapshot tests
+    /// and logs. The derived `Debug` output remains available for the full layout.
+    ///
+    /// The format is `placed <rows> holds <rows> score <score> penalties <penalties>`, where rows
+    /// are separated by `/`, and each position is written as its tile type, or `.` if empty.
+    /// Each hold row is written to its capacity.
+    /// e.x. `placed 0..../...../...../...../..... holds ./33/.../..../..... score 1 penalties 0`
+    pub fn debug_compact(&self) -> String {
+        let cell =
+            |t: &Option<Tile>| t.map_or('.', |t| char::from_digit(t as u32, 36).unwrap_or('?'));
+        let placed: Vec<String> = self
+            .placed
+            .iter()
+            .map(|row| row.iter().map(cell).collect())
+            .collect();
+        let holds: Vec<String> = self
+            .holds
+            .iter()
+            .enumerate()
+            .map(|(i, row)| row.iter().take(i + 1).map(cell).collect())
+            .collect();
+        format!(
+            "placed {} holds {} score {} penalties {}",
+            placed.join("/"),
+            holds.join("/"),
+            self.score,
+            self.penalties
+        )
+    }
+
+    /// Returns a terse one-line summary of this board for logs, in the form
+    /// `score=<score> pen=<penalties> lines=<complete horizontal lines>`.
+    /// See [`GameState::summary_lines`](crate::GameState::summary_lines) for a summary of every
+    /// player, labelled by player index.
+    pub fn summary_line(&self) -> String {
+        format!(
+            "score={} pen={} lines={}",
+            self.score,
+            self.penalties,
+            self.count_horizontal_lines()
+        )
     }
 
     /// Counts the number of complete horizontal lines in the placed section of this board.
@@ -254,11 +508,79 @@ impl Board {
             .count()
     }
 
+    /// Returns the index of each complete horizontal line in the placed section of this board,
+    /// in ascending order.
+    pub fn completed_rows(&self) -> Vec<usize> {
+        self.placed
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.iter().all(|x| x.is_some()))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns true if this board alone meets the standard game-ending condition, having completed
+    /// at least one horizontal line. Unlike [`GameState::is_game_over`](crate::GameState::is_game_over),
+    /// this considers neither the other players nor any rules variant.
+    pub fn triggers_game_end(&self) -> bool {
+        self.count_horizontal_lines() >= END_ON_LINES
+    }
+
     /// Score getter
     pub fn get_score(&self) -> usize {
         self.score
     }
 
+    /// Returns the wall of this board for rendering, indexed by row and then column. Each cell
+    /// holds the tile type which belongs at that position, and whether a tile has been placed
+    /// there, so renderers can draw empty positions as faint versions of their tile type.
+    pub fn render_matrix(&self) -> [[(Tile, bool); BOARD_DIMENSION]; BOARD_DIMENSION] {
+        std::array::from_fn(|row| {
+            std::array::from_fn(|col| {
+                (
+                    Board::get_tile_type_at_pos(row, col),
+                    self.placed[row][col].is_some(),
+                )
+            })
+        })
+    }
+
+    /// Returns the points a tile of the given type would score for adjacency if it were placed
+    /// from the given wall row now, given the tiles already on the wall. Bonuses aren't included.
+    /// Returns `None` for the floor, which places nothing.
+    pub fn score_preview(&self, tile_type: Tile, row: Row) -> Option<usize> {
+        match row {
+            Row::Floor => None,
+            Row::Wall(row_idx) => Some(Board::points_at(
+                &self.placed,
+                row_idx,
+                Board::get_tile_place_col(tile_type, row_idx),
+            )),
+        }
+    }
+
+    /// Suggests the wall row to hold tiles of the given type in, as the valid row whose tile
+    /// would score the most according to [`Board::score_preview`]. Ties go to the row with the
+    /// least space left, since it is closest to completion, and then to the higher row.
+    ///
+    /// Returns `None` if no wall row may hold the tile type, leaving only the floor.
+    pub fn best_row_for(&self, tile_type: Tile) -> Option<Row> {
+        self.valid_rows_with_capacity(tile_type)
+            .into_iter()
+            .filter_map(|(row, space)| Some((row, space, self.score_preview(tile_type, row)?)))
+            .min_by_key(|&(_, space, points)| (std::cmp::Reverse(points), space))
+            .map(|(row, _, _)| row)
+    }
+
+    /// Returns the status of each hold row of this board, from the top: `None` if the row is
+    /// empty, or the type of tile it holds and how many.
+    pub fn hold_summary(&self) -> [Option<(Tile, usize)>; BOARD_DIMENSION] {
+        std::array::from_fn(|row| {
+            let mut held = self.holds[row].iter().flatten();
+            held.next().map(|&tile_type| (tile_type, 1 + held.count()))
+        })
+    }
+
     /// Returns the type of tile that can be placed at `row` and `col` on this board.
     pub fn get_tile_type_at_pos(row: usize, col: usize) -> Tile {
         ((col + BOARD_DIMENSION - row) % BOARD_DIMENSION) as Tile
@@ -275,9 +597,56 @@ impl Board {
         (tile_type + row_idx) % BOARD_DIMENSION
     }
 
+    /// Returns the points a tile placed at `row` and `col` scores for adjacency, given the tiles
+    /// already placed. Walks horizontally and vertically, counting the lengths of each group.
+    fn points_at(
+        placed: &[[Option<Tile>; BOARD_DIMENSION]; BOARD_DIMENSION],
+        row: usize,
+        col: usize,
+    ) -> usize {
+        let (row, col) = (row as isize, col as isize);
+        let h_line = 1
+            + Board::count_in_direction(placed, row, col, 0, 1)
+            + Board::count_in_direction(placed, row, col, 0, -1);
+        let v_line = 1
+            + Board::count_in_direction(placed, row, col, 1, 0)
+            + Board::count_in_direction(placed, row, col, -1, 0);
+        Board::adjacency_points(h_line, v_line)
+    }
+
+    /// Returns the points scored by a newly placed tile, given the lengths of the horizontal and
+    /// vertical lines of tiles it belongs to, each including the tile itself.
+    ///
+    /// - An isolated tile scores 1 point.
+    /// - Otherwise, each line longer than the tile alone scores its full length, so a tile in a
+    ///   horizontal run of `k` scores `k`, and a tile joining both a horizontal run of `h` and a
+    ///   vertical run of `v` scores `h + v`. A line of length 1 scores nothing, so a tile is never
+    ///   counted an extra time for the axis it doesn't extend.
+    fn adjacency_points(h_line: usize, v_line: usize) -> usize {
+        // If the tile is alone, don't double-count it
+        if h_line == 1 && v_line == 1 {
+            return 1;
+        }
+        // Otherwise, we count the score for axes with more tiles than one
+        [h_line, v_line].into_iter().filter(|&l| l > 1).sum()
+    }
+
+    /// Returns the points lost for each slot of the floor, as given by [`FLOOR_PENALTIES`].
+    /// Index `i` is the marginal point cost of the `(i + 1)`-th penalty tile.
+    pub fn penalty_schedule() -> &'static [usize] {
+        &FLOOR_PENALTIES
+    }
+
+    /// Returns the points which would be lost to one more penalty tile on this board's floor,
+    /// given the penalty tiles already there. Once every slot of the floor is filled, further
+    /// penalty tiles cost nothing.
+    pub fn next_floor_penalty(&self) -> usize {
+        FLOOR_PENALTIES.get(self.penalties).copied().unwrap_or(0)
+    }
+
     /// Returns the number of penalty points associated with the given number of penalty tiles.  
     fn get_penalty_point_value(penalty_tiles: usize) -> usize {
-        [1, 1, 2, 2, 2, 3, 3].iter().take(penalty_tiles).sum()
+        FLOOR_PENALTIES.iter().take(penalty_tiles).sum()
     }
 
     /// Counts the number of tiles in any given direction (`drow` and `dcol`) from a source `row` and `col`.
@@ -288,6 +657,9 @@ impl Board {
         drow: isize,
         dcol: isize,
     ) -> usize {
+        #[cfg(test)]
+        LINE_WALKS.with(|walks| walks.set(walks.get() + 1));
+
         let mut count = 0;
         loop {
             row += drow;
@@ -308,11 +680,66 @@ impl Board {
 /// Struct for nicely packaging bonus types together for a board.
 /// Each property simply represents whether or not the bonus for that
 /// row, column, or tile type has been collected.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct BonusTypes {
     pub rows: [bool; BOARD_DIMENSION],
     pub columns: [bool; BOARD_DIMENSION],
-    pub tile_types: [bool; BOARD_DIMENSION],
+    pub tile_types: [bool; TILE_TYPES],
+}
+
+/// Describes everything scored by a board at the end of a round, as given by [`Board::place_holds`].
+/// # Properties
+/// * `placed`: the wall position, as `(row, col)`, of each tile placed from a completed hold.
+/// * `placement_points`: the points scored for adjacency by the placed tiles.
+/// * `bonuses`: each bonus collected, in the order given by [`Board::collectible_bonuses`].
+/// * `penalty_points`: the points lost to floor penalties, before the score is limited to zero.
+/// * `discarded`: the leftover tiles from each completed hold, which leave play to the discard.
+///   When playing a variant where incomplete holds are cleared each round, their tiles are
+///   included as well.
+/// * `score_change`: the net change in score, including placements, bonuses, and penalties after
+///   the score is limited to zero, so it may be negative.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ScoreReport {
+    pub placed: Vec<(usize, usize)>,
+    pub placement_points: usize,
+    pub bonuses: Vec<BonusEvent>,
+    pub penalty_points: usize,
+    pub discarded: Vec<Tile>,
+    pub score_change: isize,
+}
+
+/// The number of tiles remaining to be placed to complete each row, column, and tile type of a
+/// board, as given by [`Board::completion_gaps`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompletionGaps {
+    pub rows: [usize; BOARD_DIMENSION],
+    pub columns: [usize; BOARD_DIMENSION],
+    pub colors: [usize; TILE_TYPES],
+}
+
+/// A bonus which can be collected by a board.
+/// Bonuses are ordered by kind, with rows before columns before tile types, and then by index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BonusEvent {
+    /// The bonus for completing the given wall row.
+    Row(usize),
+    /// The bonus for completing the given wall column.
+    Column(usize),
+    /// The bonus for placing every tile of the given type.
+    Color(Tile),
+}
+
+/// Describes which invariant a board breaks, as found by [`Board::is_valid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoardInvariantError {
+    /// A placed tile does not match the tile type of its position on the fixed wall.
+    MisplacedTile { row: usize, col: usize },
+    /// A hold row contains more tiles than its capacity.
+    HoldOverCapacity { row: usize },
+    /// A hold row contains tiles of more than one type.
+    MixedHold { row: usize },
+    /// A hold row contains tiles of a type which is already placed in the same wall row.
+    HeldColorAlreadyPlaced { row: usize },
 }
 
 /// TODO: docstrings for this
@@ -361,3 +788,449 @@ impl BoardBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    /// Places a tile at each of the given wall positions in turn, by completing the hold of its
+    /// row and scoring it, so the board's score is realized as in play.
+    fn board_placing(positions: impl IntoIterator<Item = (usize, usize)>) -> Board {
+        let mut board = Board::default();
+        for (row, col) in positions {
+            let tile_type = Board::get_tile_type_at_pos(row, col);
+            board
+                .hold_tiles(tile_type, row + 1, Row::Wall(row), 0)
+                .unwrap();
+            board.place_holds();
+        }
+        board
+    }
+
+    /// Every wall position, by row and then column.
+    fn all_positions() -> impl Iterator<Item = (usize, usize)> {
+        (0..BOARD_DIMENSION).flat_map(|row| (0..BOARD_DIMENSION).map(move |col| (row, col)))
+    }
+
+    #[test]
+    fn max_achievable_score_bounds_the_realized_score() {
+        let nearly_full = board_placing(all_positions().skip(1));
+        assert!(Board::default().max_achievable_score() > nearly_full.get_score());
+        assert!(nearly_full.max_achievable_score() > nearly_full.get_score());
+
+        let full = board_placing(all_positions());
+        assert_eq!(full.max_achievable_score(), full.get_score());
+    }
+
+    /// A board scored at 7 points, with a completed hold in the top row and two penalty tiles.
+    fn board_with_hold() -> Board {
+        let mut holds = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        holds[0][0] = Some(0);
+        Board::builder().holds(holds).score(7).penalties(2).build()
+    }
+
+    #[test]
+    fn zero_weights_evaluate_raw_score() {
+        let board = board_with_hold();
+        assert_eq!(
+            board.evaluate(&EvalWeights::zero()),
+            board.get_score() as f32
+        );
+        assert_eq!(Board::default().evaluate(&EvalWeights::zero()), 0.);
+    }
+
+    #[test]
+    fn weights_scale_their_features() {
+        let board = board_with_hold();
+        let placement = EvalWeights {
+            placement: 1.,
+            ..EvalWeights::zero()
+        };
+        assert_eq!(board.evaluate(&placement), 8.);
+
+        let floor = EvalWeights {
+            floor_penalty: 1.,
+            ..EvalWeights::zero()
+        };
+        let penalty_points = Board::get_penalty_point_value(2) as f32;
+        assert_eq!(board.evaluate(&floor), 7. - penalty_points);
+    }
+
+    #[test]
+    fn validation_finds_corrupted_boards() {
+        let mut legit = board_placing([(0, 0), (1, 1), (2, 0)]);
+        legit.hold_tiles(1, 2, Row::Wall(3), 0).unwrap();
+        assert_eq!(legit.is_valid(), Ok(()));
+
+        let mut misplaced = legit;
+        misplaced.placed[4][2] = Some(0);
+        assert_eq!(
+            misplaced.is_valid(),
+            Err(BoardInvariantError::MisplacedTile { row: 4, col: 2 })
+        );
+
+        let mut over_capacity = legit;
+        over_capacity.holds[0][1] = Some(2);
+        assert_eq!(
+            over_capacity.is_valid(),
+            Err(BoardInvariantError::HoldOverCapacity { row: 0 })
+        );
+
+        let mut mixed = legit;
+        mixed.holds[3][2] = Some(4);
+        assert_eq!(
+            mixed.is_valid(),
+            Err(BoardInvariantError::MixedHold { row: 3 })
+        );
+
+        // Tile type 0 is already placed in the top row
+        let mut already_placed = legit;
+        already_placed.holds[0][0] = Some(0);
+        assert_eq!(
+            already_placed.is_valid(),
+            Err(BoardInvariantError::HeldColorAlreadyPlaced { row: 0 })
+        );
+    }
+
+    #[test]
+    fn penalty_preview_counts_existing_floor_tiles() {
+        let board = Board::builder().penalties(1).build();
+        assert_eq!(board.penalty_preview(2), 1 + 1 + 2);
+        assert_eq!(board.penalty_preview(0), board.pending_penalty_points());
+        assert_eq!(board.penalty_preview(20), FLOOR_PENALTIES.iter().sum());
+    }
+
+    #[test]
+    fn completed_column_is_the_only_collectible_bonus() {
+        let column = (0..BOARD_DIMENSION).map(|row| (row, 2));
+        let mut board = Board::builder()
+            .placed(board_placing(column).placed)
+            .build();
+        assert_eq!(board.collectible_bonuses(), [BonusEvent::Column(2)]);
+
+        // Collected bonuses are no longer reported
+        board.place_holds();
+        assert!(board.collectible_bonuses().is_empty());
+    }
+
+    #[test]
+    fn completion_gaps_match_a_hand_count() {
+        let board = board_placing([(0, 0), (0, 1), (1, 1), (2, 2), (3, 2)]);
+        assert_eq!(
+            board.completion_gaps(),
+            CompletionGaps {
+                rows: [3, 4, 4, 4, 5],
+                columns: [4, 3, 3, 5, 5],
+                colors: [2, 4, 5, 5, 4],
+            }
+        );
+    }
+
+    #[test]
+    fn completion_gaps_take_colors_from_the_wall() {
+        let mut placed = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        placed[1][0] = Some(TILE_TYPES);
+        let board = Board::builder().placed(placed).build();
+        assert_eq!(
+            board.completion_gaps().colors,
+            board_placing([(1, 0)]).completion_gaps().colors
+        );
+    }
+
+    #[test]
+    fn holding_into_an_overfull_row_overflows_to_the_floor() {
+        let mut holds = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        holds[0][..2].fill(Some(0));
+        let mut board = Board::builder().holds(holds).build();
+        assert_eq!(board.hold_tiles(0, 2, Row::Wall(0), 0), Ok(0));
+        assert_eq!(*board.penalties(), 2);
+        assert_eq!(board.holds()[0], holds[0]);
+    }
+
+    #[test]
+    fn color_bonus_needs_every_tile_type_placement() {
+        // Tile type 0 belongs on the diagonal
+        let diagonal: Vec<_> = (0..BOARD_DIMENSION).map(|i| (i, i)).collect();
+        let mut board = board_placing(diagonal[..TILE_TYPES - 1].iter().copied());
+        assert_eq!(board.placed_color_counts()[0], TILE_TYPES - 1);
+        assert!(!board.bonuses().tile_types[0]);
+
+        let (row, col) = diagonal[TILE_TYPES - 1];
+        board.hold_tiles(0, row + 1, Row::Wall(row), 0).unwrap();
+        let report = board.place_holds();
+        assert_eq!(report.placed, [(row, col)]);
+        assert_eq!(report.bonuses, [BonusEvent::Color(0)]);
+        assert!(board.bonuses().tile_types[0]);
+        assert_eq!(board.bonuses().tile_types.len(), TILE_TYPES);
+    }
+
+    #[test]
+    fn valid_rows_report_remaining_capacity() {
+        let mut board = Board::default();
+        board.hold_tiles(1, 2, Row::Wall(3), 0).unwrap();
+        board.hold_tiles(2, 1, Row::Wall(2), 0).unwrap();
+
+        assert_eq!(
+            board.valid_rows_with_capacity(1),
+            [
+                (Row::Wall(0), 1),
+                (Row::Wall(1), 2),
+                (Row::Wall(3), 2),
+                (Row::Wall(4), 5),
+                (Row::Floor, usize::MAX),
+            ]
+        );
+    }
+
+    #[test]
+    fn compact_debug_matches_snapshot() {
+        let mut board = board_placing([(0, 0)]);
+        board.hold_tiles(3, 2, Row::Wall(1), 0).unwrap();
+        assert_eq!(
+            board.debug_compact(),
+            "placed 0..../...../...../...../..... holds ./33/.../..../..... score 1 penalties 0"
+        );
+        assert_eq!(
+            board_with_hold().debug_compact(),
+            "placed ...../...../...../...../..... holds 0/../.../..../..... score 7 penalties 2"
+        );
+    }
+
+    /// Places a tile at the given position on a board already holding the given placements,
+    /// returning the points scored for it.
+    fn points_for(existing: &[(usize, usize)], (row, col): (usize, usize)) -> usize {
+        let mut board = board_placing(existing.iter().copied());
+        board
+            .hold_tiles(
+                Board::get_tile_type_at_pos(row, col),
+                row + 1,
+                Row::Wall(row),
+                0,
+            )
+            .unwrap();
+        board.place_holds().placement_points
+    }
+
+    #[test]
+    fn placements_score_each_line_once() {
+        // An isolated tile scores a single point
+        assert_eq!(points_for(&[], (2, 2)), 1);
+        assert_eq!(points_for(&[(0, 0)], (2, 2)), 1);
+
+        // A horizontal or vertical run scores its length, without counting the tile twice
+        assert_eq!(points_for(&[(2, 0), (2, 1)], (2, 2)), 3);
+        assert_eq!(points_for(&[(2, 1), (2, 3), (2, 4)], (2, 2)), 4);
+        assert_eq!(points_for(&[(0, 2), (1, 2)], (2, 2)), 3);
+        assert_eq!(points_for(&[(3, 2), (4, 2)], (2, 2)), 3);
+
+        // A tile joining both a row and a column scores both lines
+        assert_eq!(points_for(&[(2, 1), (1, 2)], (2, 2)), 4);
+        assert_eq!(
+            points_for(&[(2, 1), (2, 3), (1, 2), (3, 2), (4, 2)], (2, 2)),
+            7
+        );
+    }
+
+    #[test]
+    fn rows_are_reported_before_columns() {
+        let mut board = board_placing([
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (4, 0),
+        ]);
+        board
+            .hold_tiles(Board::get_tile_type_at_pos(0, 0), 1, Row::Wall(0), 0)
+            .unwrap();
+        let report = board.place_holds();
+        assert_eq!(report.bonuses, [BonusEvent::Row(0), BonusEvent::Column(0)]);
+    }
+
+    #[test]
+    fn full_rows_trigger_the_game_end() {
+        assert!(!Board::default().triggers_game_end());
+        assert!(!board_placing((0..BOARD_DIMENSION - 1).map(|col| (1, col))).triggers_game_end());
+        assert!(board_placing((0..BOARD_DIMENSION).map(|col| (1, col))).triggers_game_end());
+    }
+
+    #[test]
+    fn summary_line_lists_score_penalties_and_lines() {
+        let mut board = board_placing((0..BOARD_DIMENSION).map(|col| (3, col)));
+        board.hold_tiles(0, 2, Row::Floor, 0).unwrap();
+        let score = board.get_score();
+        assert_eq!(
+            board.summary_line(),
+            format!("score={} pen=2 lines=1", score)
+        );
+        assert_eq!(board_with_hold().summary_line(), "score=7 pen=2 lines=0");
+    }
+
+    #[test]
+    fn penalty_schedule_matches_the_rulebook() {
+        assert_eq!(Board::penalty_schedule(), [1, 1, 2, 2, 2, 3, 3]);
+        // The marginal costs add up to the total penalty for that many floor tiles
+        for tiles in 0..=FLOOR_PENALTIES.len() {
+            let marginal: usize = Board::penalty_schedule()[..tiles].iter().sum();
+            assert_eq!(
+                Board::builder()
+                    .penalties(tiles)
+                    .build()
+                    .pending_penalty_points(),
+                marginal
+            );
+        }
+    }
+
+    #[test]
+    fn completed_rows_are_listed_in_order() {
+        let board = board_placing(
+            [3, 0]
+                .into_iter()
+                .flat_map(|row| (0..BOARD_DIMENSION).map(move |col| (row, col)))
+                .chain([(1, 0), (1, 1)]),
+        );
+        assert_eq!(board.completed_rows(), [0, 3]);
+        assert!(Board::default().completed_rows().is_empty());
+    }
+
+    #[test]
+    fn incomplete_holds_skip_line_walks() {
+        let mut holds = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        holds[2][0] = Some(1);
+        holds[4] = [Some(3), Some(3), Some(3), Some(3), None];
+        let mut board = Board::builder().holds(holds).build();
+
+        LINE_WALKS.with(|walks| walks.set(0));
+        let report = board.place_holds();
+        assert!(report.placed.is_empty());
+        assert_eq!(LINE_WALKS.with(|walks| walks.get()), 0);
+
+        // A completed hold walks each of the four directions from its tile
+        board_with_hold().place_holds();
+        assert_eq!(LINE_WALKS.with(|walks| walks.get()), 4);
+    }
+
+    #[test]
+    fn tile_types_cover_every_color() {
+        assert!(tile_types().eq(0..BOARD_DIMENSION));
+        assert_eq!(tile_types().count(), TILE_TYPES);
+    }
+
+    #[test]
+    fn placed_colors_are_counted() {
+        let (blue, red) = (Color::Blue.tile(), Color::Red.tile());
+        let of_type = |tile_type| {
+            all_positions()
+                .filter(move |&(row, col)| Board::get_tile_type_at_pos(row, col) == tile_type)
+        };
+        let board = board_placing(of_type(blue).take(3).chain(of_type(red).take(1)));
+        let mut expected = [0; TILE_TYPES];
+        expected[blue] = 3;
+        expected[red] = 1;
+        assert_eq!(board.placed_color_counts(), expected);
+    }
+
+    #[test]
+    fn applying_penalties_clears_the_floor() {
+        let mut board = Board::builder().score(10).build();
+        board.hold_tiles(0, 3, Row::Floor, 0).unwrap();
+        assert_eq!(board.apply_penalties(), 4);
+        assert_eq!(board.get_score(), 6);
+        assert_eq!(*board.penalties(), 0);
+        assert_eq!(board.apply_penalties(), 0);
+    }
+
+    #[test]
+    fn render_matrix_shows_the_wall_and_placements() {
+        let placed = [(0, 0), (2, 3), (4, 1)];
+        let matrix = board_placing(placed).render_matrix();
+        for (row, col) in all_positions() {
+            let (tile_type, is_placed) = matrix[row][col];
+            assert_eq!(tile_type, Board::get_tile_type_at_pos(row, col));
+            assert_eq!(is_placed, placed.contains(&(row, col)));
+        }
+    }
+
+    #[test]
+    fn next_floor_penalty_follows_the_schedule() {
+        assert_eq!(Board::default().next_floor_penalty(), 1);
+        assert_eq!(
+            Board::builder().penalties(1).build().next_floor_penalty(),
+            1
+        );
+        assert_eq!(
+            Board::builder().penalties(2).build().next_floor_penalty(),
+            2
+        );
+        let full = Board::builder().penalties(FLOOR_PENALTIES.len()).build();
+        assert_eq!(full.next_floor_penalty(), 0);
+    }
+
+    #[test]
+    fn hold_summary_lists_each_row() {
+        let mut board = Board::default();
+        board.hold_tiles(2, 1, Row::Wall(2), 0).unwrap();
+        board.hold_tiles(4, 3, Row::Wall(4), 0).unwrap();
+        board.hold_tiles(1, 1, Row::Wall(0), 0).unwrap();
+        assert_eq!(
+            board.hold_summary(),
+            [Some((1, 1)), None, Some((2, 1)), None, Some((4, 3))]
+        );
+        assert_eq!(Board::default().hold_summary(), [None; BOARD_DIMENSION]);
+    }
+
+    #[test]
+    fn best_row_extends_a_scoring_run() {
+        let tile_type = Board::get_tile_type_at_pos(2, 2);
+        let board = board_placing([(2, 0), (2, 1)]);
+        assert_eq!(board.best_row_for(tile_type), Some(Row::Wall(2)));
+
+        // Without a run to extend, the row closest to completion is chosen
+        assert_eq!(Board::default().best_row_for(tile_type), Some(Row::Wall(0)));
+
+        // Once the tile type is placed in every row, only the floor is left
+        let col_of = |row| {
+            (0..BOARD_DIMENSION)
+                .find(|&col| Board::get_tile_type_at_pos(row, col) == tile_type)
+                .unwrap()
+        };
+        let full = board_placing((0..BOARD_DIMENSION).map(|row| (row, col_of(row))));
+        assert_eq!(full.best_row_for(tile_type), None);
+    }
+
+    #[test]
+    fn pending_penalty_points_follow_the_schedule() {
+        let mut board = Board::default();
+        assert_eq!(board.pending_penalty_points(), 0);
+        board.hold_tiles(0, 3, Row::Floor, 0).unwrap();
+        assert_eq!(*board.penalties(), 3);
+        assert_eq!(board.pending_penalty_points(), 1 + 1 + 2);
+    }
+
+    #[test]
+    fn can_hold_checks_held_and_placed_colors() {
+        let placed = Board::get_tile_type_at_pos(3, 2);
+        let mut board = board_placing([(3, 2)]);
+        board.hold_tiles(1, 1, Row::Wall(2), 0).unwrap();
+
+        assert!(board.can_hold(1, Row::Wall(2)));
+        assert!(board.can_hold(0, Row::Wall(4)));
+        assert!(!board.can_hold(0, Row::Wall(2)));
+        assert_eq!(
+            board.check_hold(0, Row::Wall(2)),
+            Err(IllegalMoveReason::RowColorConflict)
+        );
+        assert!(!board.can_hold(placed, Row::Wall(3)));
+        assert_eq!(
+            board.check_hold(placed, Row::Wall(3)),
+            Err(IllegalMoveReason::RowAlreadyHasColorPlaced)
+        );
+        assert!(board.can_hold(placed, Row::Floor));
+        assert!(!board.can_hold(0, Row::Wall(BOARD_DIMENSION)));
+    }
+}
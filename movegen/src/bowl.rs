@@ -1,12 +1,14 @@
-use crate::Tile;
+use crate::{Tile, fen::ParseFenError};
 
 /// A structure for holding groups of tiles according to Azul's bowl rules.
-#[derive(Debug, Default)]
+/// A bowl's tiles are always kept sorted in ascending order, however it was built.
+#[derive(Debug, PartialEq, Default)]
 pub struct Bowl {
     tiles: Vec<Tile>,
 }
 
 impl Bowl {
+    /// Creates a bowl holding the given tiles, which are sorted.
     pub fn from_tiles(tiles: Vec<Tile>) -> Self {
         let mut bowl = Bowl::default();
         bowl.fill(tiles);
@@ -25,6 +27,11 @@ impl Bowl {
         self.tiles.sort();
     }
 
+    /// Removes all tiles from this bowl, retaining its allocation.
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+    }
+
     /// Returns the tiles of the given type from this bowl, as well as the remaining tiles. Calling this function
     /// clears this bowl's stored tiles.
     pub fn take_tiles(&mut self, tile_type: Tile) -> (Vec<Tile>, Vec<Tile>) {
@@ -41,8 +48,10 @@ impl Bowl {
         (take, keep)
     }
 
-    /// Returns a `Vec<Tile>` of all unique tile types owned by this bowl.
+    /// Returns a `Vec<Tile>` of all unique tile types owned by this bowl, in ascending order.
     pub fn get_tile_types(&self) -> Vec<Tile> {
+        // Every constructor sorts the tiles, which dedup relies on to remove every duplicate
+        debug_assert!(self.tiles.is_sorted(), "Bowl tiles are not sorted");
         let mut tiles = self.tiles.clone();
         tiles.dedup();
         tiles
@@ -52,6 +61,29 @@ impl Bowl {
     pub fn tiles(&self) -> &Vec<Tile> {
         &self.tiles
     }
+
+    /// Creates a bowl from the given AzulFEN bowl component, where each character is a tile type
+    /// and an empty bowl is denoted by `-`. Surrounding whitespace is ignored.
+    /// Will error if the component is blank, since an empty bowl must still be written as `-`.
+    pub fn from_fen(fen: &str) -> Result<Self, ParseFenError> {
+        match fen.trim() {
+            "" => Err(ParseFenError),
+            "-" => Ok(Bowl::default()),
+            fen => Ok(Bowl::from_tiles(
+                fen.chars()
+                    .map(|c| c.to_string().parse::<Tile>().or(Err(ParseFenError)))
+                    .collect::<Result<Vec<_>, ParseFenError>>()?,
+            )),
+        }
+    }
+
+    /// Returns the AzulFEN bowl component for this bowl.
+    pub fn to_fen(&self) -> String {
+        if self.tiles.is_empty() {
+            return String::from("-");
+        }
+        self.tiles.iter().map(|t| t.to_string()).collect()
+    }
 }
 
 impl Clone for Bowl {
@@ -61,3 +93,35 @@ impl Clone for Bowl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fen_round_trips() {
+        let empty = Bowl::default();
+        assert_eq!(empty.to_fen(), "-");
+        assert_eq!(Bowl::from_fen(&empty.to_fen()).unwrap(), empty);
+
+        let full = Bowl::from_tiles(vec![3, 0, 4, 0]);
+        assert_eq!(full.to_fen(), "0034");
+        assert_eq!(Bowl::from_fen(&full.to_fen()).unwrap(), full);
+    }
+
+    #[test]
+    fn empty_bowls_are_written_as_a_dash() {
+        assert_eq!(Bowl::from_fen("-").unwrap(), Bowl::default());
+        assert_eq!(*Bowl::from_fen("0123").unwrap().tiles(), [0, 1, 2, 3]);
+        assert!(Bowl::from_fen("").is_err());
+        assert!(Bowl::from_fen("  ").is_err());
+    }
+
+    #[test]
+    fn bowls_keep_their_tiles_sorted() {
+        let bowl = Bowl::from_fen("2010").unwrap();
+        assert_eq!(*bowl.tiles(), [0, 0, 1, 2]);
+        assert_eq!(bowl.get_tile_types(), [0, 1, 2]);
+        assert_eq!(Bowl::from_tiles(vec![2, 0, 1, 0]), bowl);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Tile, board::TILE_TYPES};
+
+/// The colours of the standard Azul tiles, ordered by their tile type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Blue,
+    Yellow,
+    Red,
+    Black,
+    White,
+}
+
+impl Color {
+    /// Every colour, ordered by tile type. The array is sized by the tile type count, so a variant
+    /// with more tile types won't compile until its colours are named here too.
+    pub const ALL: [Color; TILE_TYPES] = [
+        Color::Blue,
+        Color::Yellow,
+        Color::Red,
+        Color::Black,
+        Color::White,
+    ];
+
+    /// Returns the colour of the given tile type, if it is a standard tile type.
+    pub fn from_tile(tile_type: Tile) -> Option<Color> {
+        Color::ALL.get(tile_type).copied()
+    }
+
+    /// Returns the tile type of this colour.
+    pub fn tile(&self) -> Tile {
+        *self as Tile
+    }
+
+    /// Returns the lowercase name of this colour.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Color::Blue => "blue",
+            Color::Yellow => "yellow",
+            Color::Red => "red",
+            Color::Black => "black",
+            Color::White => "white",
+        }
+    }
+
+    /// Returns the colour with the given name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Color> {
+        Color::ALL
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+}
@@ -0,0 +1,47 @@
+/// The weights applied to each feature of a board by [`Board::evaluate`](crate::Board::evaluate).
+/// Exposed so that the evaluation can be tuned.
+/// # Properties
+/// * `placement`: scales the points, including bonuses, which the completed holds would score if
+///   they were placed now.
+/// * `row_potential`: scales the progress towards each uncollected row bonus.
+/// * `column_potential`: scales the progress towards each uncollected column bonus.
+/// * `color_potential`: scales the progress towards each uncollected tile type bonus.
+/// * `floor_penalty`: scales the points lost to the penalty tiles on the floor.
+/// * `held_stranded_penalty`: scales the number of tiles held in incomplete rows, which will be
+///   wasted if the game ends before they can be placed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvalWeights {
+    pub placement: f32,
+    pub row_potential: f32,
+    pub column_potential: f32,
+    pub color_potential: f32,
+    pub floor_penalty: f32,
+    pub held_stranded_penalty: f32,
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        EvalWeights {
+            placement: 1.,
+            row_potential: 0.5,
+            column_potential: 0.5,
+            color_potential: 0.5,
+            floor_penalty: 1.,
+            held_stranded_penalty: 0.25,
+        }
+    }
+}
+
+impl EvalWeights {
+    /// Returns weights which are all zero, so that only the current score is evaluated.
+    pub fn zero() -> Self {
+        EvalWeights {
+            placement: 0.,
+            row_potential: 0.,
+            column_potential: 0.,
+            color_potential: 0.,
+            floor_penalty: 0.,
+            held_stranded_penalty: 0.,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{Board, Bowl, GameState, Move};
+
+/// A read-only view of a gamestate which exposes only public information: the boards, bowls,
+/// centre, and turn order. The contents and order of the bag are hidden, so an engine given a
+/// `FairView` cannot peek at future draws.
+#[derive(Debug, Clone, Copy)]
+pub struct FairView<'a> {
+    state: &'a GameState,
+}
+
+impl<'a> FairView<'a> {
+    /// Creates a fair view of the given gamestate.
+    pub fn new(state: &'a GameState) -> Self {
+        FairView { state }
+    }
+
+    /// Getter for the index of the player whose turn it is.
+    pub fn active_player(&self) -> usize {
+        *self.state.active_player()
+    }
+
+    /// Getter for the boards of every player.
+    pub fn boards(&self) -> &'a Vec<Board> {
+        self.state.boards()
+    }
+
+    /// Getter for the bowls, including the centre.
+    pub fn bowls(&self) -> &'a Vec<Bowl> {
+        self.state.bowls()
+    }
+
+    /// Getter for the centre area.
+    pub fn centre(&self) -> &'a Bowl {
+        self.state.centre()
+    }
+
+    /// Getter for the owner of the first player token, if it has been taken this round.
+    pub fn first_token_owner(&self) -> Option<usize> {
+        *self.state.first_token_owner()
+    }
+
+    /// Returns a list of all valid moves for the active player.
+    /// See [`GameState::get_valid_moves`].
+    pub fn get_valid_moves(&self) -> Vec<Move> {
+        self.state.get_valid_moves()
+    }
+
+    /// Returns true if all bowls are empty, otherwise false.
+    pub fn round_over(&self) -> bool {
+        self.state.round_over()
+    }
+
+    /// Returns true if the game has ended.
+    pub fn is_game_over(&self) -> bool {
+        self.state.is_game_over()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Bag;
+
+    #[test]
+    fn fair_views_hide_the_bag_but_generate_moves() {
+        let mut state = GameState::new_seeded(2, 18);
+        state.setup_next_round();
+        let other = GameState::builder()
+            .boards(state.boards().clone())
+            .bowls(state.bowls().clone())
+            .bag(Bag::new_ordered(vec![0, 1, 2]))
+            .build();
+        assert_ne!(state.bag().items(), other.bag().items());
+
+        // Views of states differing only in their bags expose exactly the same information
+        let (view, other_view) = (state.fair_view(), other.fair_view());
+        assert_eq!(view.get_valid_moves(), state.get_valid_moves());
+        assert_eq!(view.get_valid_moves(), other_view.get_valid_moves());
+        assert_eq!(view.boards(), other_view.boards());
+        assert_eq!(view.bowls(), other_view.bowls());
+        assert_eq!(view.centre(), other_view.centre());
+        assert_eq!(view.active_player(), other_view.active_player());
+        assert_eq!(view.first_token_owner(), other_view.first_token_owner());
+        assert_eq!(view.round_over(), other_view.round_over());
+        assert_eq!(view.is_game_over(), other_view.is_game_over());
+    }
+}
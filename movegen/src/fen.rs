@@ -0,0 +1,4 @@
+/// Attempting to parse an invalid AzulFEN component will produce this error.
+/// See the AzulFEN specification in the interface crate for details on the format.
+#[derive(Debug)]
+pub struct ParseFenError;
@@ -1,4 +1,10 @@
-use crate::{Tile, row::Row};
+use crate::{
+    Tile,
+    board::{BOARD_DIMENSION, TILE_TYPES},
+    color::Color,
+    row::Row,
+    tile_source::TileSource,
+};
 
 /// A move in gameplay.
 /// # Properties
@@ -12,6 +18,182 @@ pub struct Move {
     pub row: Row,
 }
 
-/// Attempting to play a move which is not valid will produce this error.
+impl Move {
+    /// Returns the area this move takes its tiles from.
+    pub fn source(&self) -> TileSource {
+        TileSource::from_bowl_index(self.bowl)
+    }
+
+    /// Parses a move from a lenient, human-readable notation.
+    ///
+    /// The notation is made up of three whitespace-separated parts, `bowl tile_type row`, ignoring case:
+    /// * `bowl`: the bowl index, optionally prefixed with `b` or `bowl` (ex. `3`, `b3`, `bowl3`),
+    ///   or `centre`/`center` for the centre area, which is the same as bowl `0`.
+    /// * `tile_type`: a colour name (ex. `blue`), or a tile type index.
+    /// * `row`: the row number counting from 1 at the top, optionally prefixed with `r` or `row`
+    ///   (ex. `2`, `r2`, `row2`), or `floor`/`0` for the floor row.
+    ///
+    /// ex. `b3 red row2` and `3 2 2` both correspond to taking tile type 2 from the third bowl
+    /// and holding it in the second row.
+    pub fn from_human(input: &str) -> Result<Move, ParseHumanMoveError> {
+        let parts: Vec<_> = input.split_whitespace().collect();
+        let [bowl, tile_type, row] = parts.as_slice() else {
+            return Err(ParseHumanMoveError);
+        };
+        let (bowl, tile_type, row) = (
+            bowl.to_ascii_lowercase(),
+            tile_type.to_ascii_lowercase(),
+            row.to_ascii_lowercase(),
+        );
+
+        let bowl = match bowl.as_str() {
+            "centre" | "center" => 0,
+            b => strip_label(b, &["bowl", "b"])
+                .parse::<usize>()
+                .or(Err(ParseHumanMoveError))?,
+        };
+
+        let tile_type = match Color::from_name(&tile_type) {
+            Some(color) => color.tile(),
+            None => tile_type.parse::<Tile>().or(Err(ParseHumanMoveError))?,
+        };
+        if tile_type >= TILE_TYPES {
+            return Err(ParseHumanMoveError);
+        }
+
+        let row = match row.as_str() {
+            "floor" => Row::Floor,
+            r => match strip_label(r, &["row", "r"])
+                .parse::<usize>()
+                .or(Err(ParseHumanMoveError))?
+            {
+                0 => Row::Floor,
+                r if r <= BOARD_DIMENSION => Row::Wall(r - 1),
+                _ => return Err(ParseHumanMoveError),
+            },
+        };
+
+        Ok(Move {
+            bowl,
+            tile_type,
+            row,
+        })
+    }
+}
+
+/// Formats the move in the six digit notation of the move protocol, `bowl tile_type row` with
+/// two digits each, where row `00` is the floor. ex. `040102`.
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:02}{:02}{:02}",
+            self.bowl,
+            self.tile_type,
+            self.row.to_protocol()
+        )
+    }
+}
+
+/// Removes the first matching label from the front of the given part of a human-readable move.
+fn strip_label<'a>(part: &'a str, labels: &[&str]) -> &'a str {
+    labels
+        .iter()
+        .find_map(|label| part.strip_prefix(label))
+        .unwrap_or(part)
+}
+
+/// Describes where the tiles went after a move was played.
+/// # Properties
+/// * `taken`: the number of tiles taken from the selected bowl.
+/// * `to_row`: the number of taken tiles which were added to the selected row.
+/// * `to_floor`: the number of taken tiles which went to the floor, either by overflowing the
+///   selected row or by selecting the floor directly. The first player token is not included.
+/// * `to_centre`: the number of tiles left over in the selected bowl which moved to the centre.
+/// * `next_player`: the index of the player whose turn it is after this move.
+/// * `round_ended`: whether this move took the last tiles of the round, in which case
+///   [`GameState::setup_next_round`](crate::GameState::setup_next_round) should be called next.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MoveReport {
+    pub taken: usize,
+    pub to_row: usize,
+    pub to_floor: usize,
+    pub to_centre: usize,
+    pub next_player: usize,
+    pub round_ended: bool,
+}
+
+/// Attempting to parse an invalid human-readable move will produce this error.
 #[derive(Debug)]
-pub struct IllegalMoveError;
+pub struct ParseHumanMoveError;
+
+/// Attempting to play a move which is not valid will produce this error.
+/// # Properties
+/// * `choice`: the move which was attempted.
+/// * `reason`: why the move is not valid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IllegalMoveError {
+    pub choice: Move,
+    pub reason: IllegalMoveReason,
+}
+
+/// Describes why a move is not valid in a given gamestate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IllegalMoveReason {
+    /// The selected bowl does not exist.
+    NoSuchBowl,
+    /// The selected bowl does not contain any tiles of the selected type.
+    ColorNotInBowl,
+    /// The selected row does not exist on the board.
+    NoSuchRow,
+    /// The selected row is already holding tiles of a different type.
+    RowColorConflict,
+    /// The selected type of tile has already been placed on the wall in the selected row.
+    RowAlreadyHasColorPlaced,
+    /// The active player does not have a board, as in a corrupted gamestate.
+    NoSuchPlayer,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_human_moves() {
+        assert_eq!(
+            Move::from_human("centre blue floor").unwrap(),
+            Move {
+                bowl: 0,
+                tile_type: Color::Blue.tile(),
+                row: Row::Floor,
+            }
+        );
+        assert_eq!(
+            Move::from_human("3 2 1").unwrap(),
+            Move {
+                bowl: 3,
+                tile_type: 2,
+                row: Row::Wall(0),
+            }
+        );
+        assert_eq!(
+            Move::from_human("B3 Red ROW2").unwrap(),
+            Move::from_human("3 2 2").unwrap()
+        );
+    }
+
+    #[test]
+    fn rejects_garbage_human_moves() {
+        for garbage in [
+            "",
+            "3 2",
+            "3 2 1 0",
+            "x 2 1",
+            "3 purple 1",
+            "3 5 1",
+            "3 2 6",
+        ] {
+            assert!(Move::from_human(garbage).is_err(), "{}", garbage);
+        }
+    }
+}
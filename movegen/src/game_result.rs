@@ -0,0 +1,8 @@
+/// The outcome of a completed game.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameResult {
+    /// A single player won the game outright. The parameter `usize` is the index of that player.
+    Win(usize),
+    /// Several players shared the victory. The parameter contains the indices of those players.
+    Draw(Vec<usize>),
+}
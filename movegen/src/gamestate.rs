@@ -1,59 +1,144 @@
+use std::sync::OnceLock;
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
 use crate::{
-    Board, Tile,
+    Board, EvalWeights, FairView, GameResult, Row, Tile,
     bag::Bag,
-    board::BOARD_DIMENSION,
+    board::{BOARD_DIMENSION, ScoreReport, TILE_TYPES, tile_types},
     bowl::Bowl,
-    game_move::{IllegalMoveError, Move},
+    game_move::{IllegalMoveError, IllegalMoveReason, Move, MoveReport},
+    rules::{FirstPlayerRule, RulesConfig},
+    tile_source::{CENTRE_BOWL_IDX, TileSource},
 };
 
-/// The number of tiles of each type to be added to the bag at the beginning of the game, and to be
-/// used for reference during round setup.
-const TILES_PER_TYPE: usize = 20;
+/// The number of rounds played in a solo game before it ends.
+const SOLO_ROUNDS: usize = 5;
 
-/// The number of tiles that each bowl is restocked to contain during the roubnd setup.
-const BOWL_CAPACITY: usize = 4;
+/// The default reward granted by [`GameState::step_reward`] to the winning player on the step
+/// which ends the game.
+pub const WIN_REWARD: f32 = 10.;
 
-/// The index of the centre tile space. Is area is not technically a bowl in the original game, but for
-/// simplicity of the code, this decision has been made here.
-const CENTRE_BOWL_IDX: usize = 0;
+/// A playout policy, which picks the index of the move to play from the given legal moves.
+pub type Policy = dyn Fn(&GameState, &[Move]) -> usize;
 
 /// Represents a complete gamestate for a given number of players.
 /// Supports generation from and serialization to a custom AzulFEN [TODO: link].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GameState {
     active_player: usize,
     boards: Vec<Board>,
     bowls: Vec<Bowl>,
     bag: Bag<Tile>,
+    discard: Vec<Tile>,
     first_token_owner: Option<usize>,
-}
-
-/// Bowl formula is given by 2n + 1, with an additional bowl for the centre space.
-fn get_bowl_count(players: usize) -> usize {
-    players * 2 + 2
-}
-
-/// Generates a default tileset for a game setup.
-/// By default, [TILES_PER_TYPE] of each tile type are given.
-fn get_default_tileset() -> Vec<Tile> {
-    let mut tiles = Vec::new();
-    // There should always be the same number of tiles as board width
-    for t in 0..BOARD_DIMENSION {
-        tiles.append(&mut vec![t as Tile; TILES_PER_TYPE]);
-    }
-    tiles
+    round_starter: usize,
+    move_count: usize,
+    round: usize,
+    last_setup_was_partial: bool,
+    last_round_placements: Vec<usize>,
+    seed: Option<u64>,
+    players_swapped: bool,
+    record_history: bool,
+    history: Vec<Move>,
+    cache_moves: bool,
+    move_cache: OnceLock<Vec<Move>>,
+    rules: RulesConfig,
 }
 
 impl GameState {
     /// Creates a new gamestate for the given number of players.
     pub fn new(players: usize) -> Self {
+        GameState::with_rules(players, RulesConfig::default())
+    }
+
+    /// Creates a new gamestate for the given number of players, where all shuffles of the bag
+    /// are determined by the given seed.
+    /// Since every bowl fill draws from the bag, two games with the same seed and the same moves
+    /// are dealt identical bowls every round.
+    pub fn new_seeded(players: usize, seed: u64) -> Self {
+        GameState::with_rules_seeded(players, RulesConfig::default(), seed)
+    }
+
+    /// Creates a new gamestate for the given number of players, played with the given rules.
+    pub fn with_rules(players: usize, rules: RulesConfig) -> Self {
+        GameState::from_bag(players, rules, Bag::new(rules.tileset()), None)
+    }
+
+    /// Creates a new gamestate for the given number of players, played with the given rules,
+    /// where all shuffles of the bag are determined by the given seed.
+    pub fn with_rules_seeded(players: usize, rules: RulesConfig, seed: u64) -> Self {
+        GameState::from_bag(
+            players,
+            rules,
+            Bag::new_seeded(rules.tileset(), seed),
+            Some(seed),
+        )
+    }
+
+    /// Creates a new gamestate at the beginning of a game, drawing from the given bag.
+    fn from_bag(players: usize, rules: RulesConfig, bag: Bag<Tile>, seed: Option<u64>) -> Self {
+        let starter = starting_player(players, &rules, seed);
         GameState {
-            active_player: 0,
+            active_player: starter,
             boards: vec![Board::default(); players],
-            bowls: vec![Bowl::default(); get_bowl_count(players)],
-            bag: Bag::new(get_default_tileset()),
+            bowls: vec![Bowl::default(); rules.bowl_count(players)],
+            bag,
+            discard: Vec::new(),
             first_token_owner: None,
+            round_starter: starter,
+            move_count: 0,
+            round: 0,
+            last_setup_was_partial: false,
+            last_round_placements: Vec::new(),
+            seed,
+            players_swapped: false,
+            record_history: false,
+            history: Vec::new(),
+            cache_moves: false,
+            move_cache: OnceLock::new(),
+            rules,
+        }
+    }
+
+    /// Resets this gamestate to the beginning of a new game with the same number of players and
+    /// deals its first round, reusing existing allocations where possible.
+    /// If a seed is given, the bag is reseeded with it before being restocked, and it decides the
+    /// starting player if the rules randomize it, so the new game is dealt exactly as
+    /// [`GameState::with_rules_seeded`] followed by [`GameState::setup_next_round`] would.
+    /// Otherwise, the bag is reseeded at random and the game is no longer seeded.
+    pub fn new_game(&mut self, seed: Option<u64>) {
+        for board in self.boards.iter_mut() {
+            board.reset();
+        }
+        for bowl in self.bowls.iter_mut() {
+            bowl.clear();
         }
+        self.bag.reseed(seed.unwrap_or_else(rand::random));
+        self.seed = seed;
+        self.players_swapped = false;
+        self.bag.refill(self.rules.tiles());
+        self.discard.clear();
+        self.active_player = starting_player(self.boards.len(), &self.rules, seed);
+        self.first_token_owner = None;
+        self.round_starter = self.active_player;
+        self.move_count = 0;
+        self.round = 0;
+        self.last_setup_was_partial = false;
+        self.last_round_placements.clear();
+        self.history.clear();
+        self.move_cache.take();
+        self.setup_next_round();
+    }
+
+    /// Generates the full set of tiles used in a standard game.
+    ///
+    /// There is one tile type for each wall column, so the number of tile types equals
+    /// [`BOARD_DIMENSION`], as given by [`TILE_TYPES`], and each type appears
+    /// [`TILES_PER_TYPE`](crate::rules::TILES_PER_TYPE) times. Tiles are grouped by type in
+    /// ascending order, before any shuffling.
+    pub fn default_tileset() -> Vec<Tile> {
+        RulesConfig::default().tileset()
     }
 
     /// Creates a new `GameStateBuilder`.
@@ -67,57 +152,280 @@ impl GameState {
         bowls: Vec<Bowl>,
         bag: Bag<Tile>,
         first_token_owner: Option<usize>,
+        round_starter: usize,
+        move_count: usize,
+        round: usize,
+        last_setup_was_partial: bool,
+        last_round_placements: Vec<usize>,
+        seed: Option<u64>,
+        players_swapped: bool,
+        history: Vec<Move>,
+        rules: RulesConfig,
     }
 
-    /// Performs a variety of tasks to setup the beginning of a round, including
-    /// - Placing held tiles
-    /// - Applying previous round penalties
-    /// - Refilling bowls
-    /// - Restocking the bag, if necessary
-    /// - Determining the first player
+    /// Returns the board of the active player.
+    /// Panics if the active player does not have a board, which can only happen in a gamestate
+    /// built without validation, see [`GameStateBuilder::try_build`].
+    pub fn active_board(&self) -> &Board {
+        self.boards.get(self.active_player).expect("Invalid player")
+    }
+
+    /// Returns the board of the active player for modification, clearing any cached moves since
+    /// they may no longer be valid. Panics in the same cases as [`GameState::active_board`].
+    pub fn active_board_mut(&mut self) -> &mut Board {
+        self.move_cache.take();
+        self.boards
+            .get_mut(self.active_player)
+            .expect("Invalid player")
+    }
+
+    /// Returns the number of tiles which have left play to the discard, either by being dropped
+    /// to the floor or as leftovers from completed rows, and have not yet been returned to the bag.
+    /// Together with the tiles in the bag, bowls, and on the boards, this always totals the
+    /// number of tiles the game started with.
+    pub fn discarded_total(&self) -> usize {
+        self.discard.len()
+    }
+
+    /// Asserts that no tiles have been created or lost, by checking that the tiles in the bag, the
+    /// bowls and centre, on the boards, and in the discard make up exactly the rules' tileset.
+    /// Only checked in debug builds, where it is called after every move and round setup.
+    /// Gamestates built without validation may hold a different set of tiles, and will fail it.
+    pub fn debug_assert_tile_conservation(&self) {
+        if cfg!(debug_assertions) {
+            let mut tiles: Vec<Tile> = self
+                .bag
+                .items()
+                .iter()
+                .chain(self.bowls.iter().flat_map(|b| b.tiles()))
+                .chain(self.discard.iter())
+                .copied()
+                .chain(self.boards.iter().flat_map(|b| b.get_active_tiles()))
+                .collect();
+            tiles.sort();
+            assert_eq!(tiles, self.rules.tileset(), "Tiles were not conserved");
+        }
+    }
+
+    /// Returns a view of this gamestate which only exposes public information, hiding the bag.
+    pub fn fair_view(&self) -> FairView<'_> {
+        FairView::new(self)
+    }
+
+    /// Enables or disables recording of played moves into this gamestate's history.
+    /// Recording is disabled by default to avoid the overhead when unused.
+    pub fn set_record_history(&mut self, record_history: bool) {
+        self.record_history = record_history;
+    }
+
+    /// Enables or disables caching of the valid moves between changes to this gamestate.
+    /// When enabled, the valid moves are generated once per turn, and repeated calls to
+    /// [`GameState::get_valid_moves`] and [`GameState::is_legal`] reuse them until a move is made
+    /// or a round is set up. Caching is disabled by default to avoid the overhead when unused.
+    pub fn set_cache_moves(&mut self, cache_moves: bool) {
+        self.cache_moves = cache_moves;
+        self.move_cache.take();
+    }
+
+    /// Returns true if this gamestate shows the same position as the other, with the same boards,
+    /// the same tiles in each bowl and the centre, and the same owner of the first player token.
+    ///
+    /// Whose turn it is, the bag and discard, the move and round counters, and the history are all
+    /// ignored, so positions reached by different move orders or with different future draws
+    /// compare equal. Use `==` to also compare these, see [`GameState`]'s `PartialEq`.
+    pub fn position_eq(&self, other: &GameState) -> bool {
+        self.boards == other.boards
+            && self.bowls == other.bowls
+            && self.first_token_owner == other.first_token_owner
+    }
+
+    /// Returns a copy of this gamestate with the players seated in reverse order, keeping the
+    /// bowls and the bag, including the order of every future draw, exactly as they are.
+    /// Each player's board, along with their turn and the first player token, passes to the
+    /// opposite seat, so in a two player game the players trade places. Swapping twice gives back
+    /// the original arrangement.
+    ///
+    /// Useful for paired games, where each engine should face the same deal from the other side.
+    /// The swap is noted in [`GameState::players_swapped`], so that the game can still be
+    /// reproduced from its seed by [`GameState::replay_from_start`].
+    pub fn with_swapped_players(&self) -> GameState {
+        let players = self.boards.len();
+        let swap = |player: usize| players.saturating_sub(player + 1);
+        let mut swapped = self.clone();
+        swapped.boards.reverse();
+        swapped.last_round_placements.reverse();
+        swapped.active_player = swap(self.active_player);
+        swapped.first_token_owner = self.first_token_owner.map(swap);
+        swapped.round_starter = swap(self.round_starter);
+        swapped.players_swapped = !self.players_swapped;
+        swapped.move_cache.take();
+        swapped
+    }
+
+    /// Rebuilds this game from its initial deal by replaying every recorded move, starting a new
+    /// round whenever the previous one is over.
+    ///
+    /// Reproducing the same draws requires the seed this game was created with, so `None` is
+    /// returned if this gamestate was not seeded. The history must also have been recorded from
+    /// the start of the game for the replay to match.
+    /// If the players were swapped, they are swapped again once the first round is dealt, as they
+    /// are for paired games.
+    pub fn replay_from_start(&self) -> Option<GameState> {
+        let mut replay = GameState::with_rules_seeded(self.player_count(), self.rules, self.seed?);
+        replay.set_record_history(true);
+        replay.setup_next_round();
+        if self.players_swapped {
+            replay = replay.with_swapped_players();
+        }
+        for choice in self.history.iter() {
+            replay.make_move(choice).expect("Recorded move was illegal");
+            if replay.round_over() {
+                replay.setup_next_round();
+            }
+        }
+        replay.set_record_history(self.record_history);
+        Some(replay)
+    }
+
+    /// Performs a variety of tasks to setup the beginning of a round, by scoring the previous round
+    /// with [`GameState::score_round`] and then dealing the next with [`GameState::deal_round`].
+    /// Drivers which want to show the round's results before the next deal may call these
+    /// separately instead, with the same effect.
+    ///
+    /// Returns a report of what each board scored at the end of the previous round, in player order.
+    pub fn setup_next_round(&mut self) -> Vec<ScoreReport> {
+        let reports = self.score_round();
+        self.deal_round();
+        self.debug_assert_tile_conservation();
+        reports
+    }
+
+    /// Scores the round which has just been played, including
+    /// - Placing held tiles, and clearing incomplete holds if the rules don't carry them over
+    /// - Applying the round's penalties
+    /// - Recording how many tiles each board placed, see [`GameState::round_is_all_penalty`]
+    /// - Determining the first player of the next round
     /// - Resetting the first player token holder
-    pub fn setup_next_round(&mut self) {
-        // Place each board's held tiles and apply penalties
+    ///
+    /// The player who took the first player token starts the next round. If nobody took it, as
+    /// when the centre is never taken from, the player who started this round starts the next
+    /// one too, see [`GameState::round_starter`]. Under [`FirstPlayerRule::Rotate`], the next
+    /// player in turn order after this round's starter starts the next round instead.
+    ///
+    /// Returns a report of what each board scored, in player order.
+    pub fn score_round(&mut self) -> Vec<ScoreReport> {
+        self.move_cache.take();
+
+        // Place each board's held tiles and apply penalties, counting the tiles placed if a
+        // round has actually been played
+        self.last_round_placements.clear();
+        let round_played = self.round > 0;
+        let mut reports = Vec::with_capacity(self.boards.len());
         for board in self.boards.iter_mut() {
-            board.place_holds();
+            let report = score_board(board, &self.rules);
+            self.discard.extend(&report.discarded);
+            if round_played {
+                self.last_round_placements.push(report.placed.len());
+            }
+            reports.push(report);
+        }
+
+        // The player with the first player's token goes first next round, or the same player
+        // goes first again if nobody took it
+        self.active_player = match self.rules.first_player_rule {
+            FirstPlayerRule::Rotate if round_played => (self.round_starter + 1) % self.boards.len(),
+            _ => self.first_token_owner.unwrap_or(self.round_starter),
+        };
+        self.round_starter = self.active_player;
+        self.first_token_owner = None;
+        reports
+    }
+
+    /// Deals the next round, including
+    /// - Clearing the centre and bowls, discarding any tiles left in them
+    /// - Refilling bowls
+    /// - Restocking the bag from the discard, if necessary, using the bag's own random number
+    ///   generator. A bowl being filled when the bag runs out keeps the tiles it has drawn, and is
+    ///   topped up from the restocked bag
+    /// - Advancing the round counter
+    ///
+    /// If too few tiles remain to completely fill every bowl, the bowls are filled with what
+    /// remains and [`GameState::last_setup_was_partial`] is set, signalling that the game is
+    /// winding down. Partially filled bowls are played as normal.
+    pub fn deal_round(&mut self) {
+        self.move_cache.take();
+        self.round += 1;
+
+        // Tiles are only left over if the previous round wasn't played out, such as in a crafted
+        // position, and are discarded rather than carried into the new round
+        for bowl in self.bowls.iter_mut() {
+            self.discard.extend(bowl.tiles());
+            bowl.clear();
         }
 
         // Fill each bowl, skipping the centre
-        let (bowls, bag) = (&mut self.bowls, &mut self.bag);
-        for bowl in bowls.iter_mut().skip(1) {
-            let mut next: Vec<Tile> = bag.take(BOWL_CAPACITY).collect();
-            if next.len() < BOWL_CAPACITY {
-                // Refill the bag with all tiles currently not in play
-                let mut used_tiles = Vec::new();
-                for board in &self.boards {
-                    used_tiles.extend(board.get_active_tiles());
-                }
-                let mut unused_tiles = Vec::new();
-                for t in 0..BOARD_DIMENSION {
-                    unused_tiles.append(&mut vec![
-                        t as Tile;
-                        TILES_PER_TYPE
-                            - used_tiles
-                                .iter()
-                                .filter(|&&x| x == t as Tile)
-                                .count()
-                    ]);
-                }
-                bag.restock(unused_tiles);
+        self.last_setup_was_partial = false;
+        let (bowls, bag, discard) = (&mut self.bowls, &mut self.bag, &mut self.discard);
+        for (_, bowl) in bowls
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| TileSource::from_bowl_index(*i) != TileSource::Centre)
+        {
+            let mut next = bag.draw(self.rules.bowl_capacity);
+            if next.len() < self.rules.bowl_capacity {
+                // The bag is empty, so refill it with all discarded tiles and draw the remainder
+                bag.restock(std::mem::take(discard));
+            }
+            next.extend(bag.draw(self.rules.bowl_capacity - next.len()));
+            if next.len() < self.rules.bowl_capacity {
+                self.last_setup_was_partial = true;
             }
-            next.extend(bag.take(BOWL_CAPACITY - next.len()));
             bowl.fill(next.clone());
         }
-
-        // At the end of setup, the player with the first player's token goes first
-        self.active_player = self.first_token_owner.unwrap_or_default();
-        self.first_token_owner = None;
     }
 
     /// Returns a list of all valid moves in the current gamestate.
     /// This list includes penalizing moves, such as placing tiles to the floor position.
+    ///
+    /// Moves are listed in a canonical order, which may be relied upon:
+    /// - By bowl index, ascending, beginning with the centre
+    /// - Then by tile type, ascending, since [`Bowl::get_tile_types`] is sorted
+    /// - Then by row, with the wall rows ascending and the floor last
+    ///
+    /// Bowls with identical contents are interchangeable, since taking the same tiles from either
+    /// leads to the same position apart from which bowl is left empty. Their moves are still all
+    /// listed, with those of the lower-indexed bowl first, so the order never depends on contents.
     pub fn get_valid_moves(&self) -> Vec<Move> {
-        let board = self.boards.get(self.active_player).expect("Invalid player");
+        if self.cache_moves {
+            return self
+                .move_cache
+                .get_or_init(|| self.valid_moves_for(self.active_player))
+                .clone();
+        }
+        self.valid_moves_for(self.active_player)
+    }
+
+    /// Returns a list of all valid moves in the current gamestate, as with
+    /// [`GameState::get_valid_moves`], or `None` if the active player does not have a board.
+    /// This never panics, so it is suitable for gamestates built from untrusted input.
+    pub fn try_get_valid_moves(&self) -> Option<Vec<Move>> {
+        self.boards.get(self.active_player)?;
+        Some(self.get_valid_moves())
+    }
+
+    /// Returns a list of all moves which would be valid for the given player in the current
+    /// gamestate, as if it were their turn.
+    /// Unless the given player is the active player, these moves cannot currently be played.
+    /// Moves are listed in the same canonical order as [`GameState::get_valid_moves`].
+    /// Will panic if the given player does not have a board.
+    pub fn valid_moves_for(&self, player: usize) -> Vec<Move> {
+        self.try_valid_moves_for(player).expect("Invalid player")
+    }
+
+    /// Returns a list of all moves which would be valid for the given player, as with
+    /// [`GameState::valid_moves_for`], or `None` if the given player does not have a board.
+    pub fn try_valid_moves_for(&self, player: usize) -> Option<Vec<Move>> {
+        let board = self.boards.get(player)?;
         let mut moves = Vec::new();
         for (bowl_idx, bowl) in self.bowls.iter().enumerate() {
             for tile in bowl.get_tile_types() {
@@ -130,118 +438,2318 @@ impl GameState {
                 }
             }
         }
-        moves
+        Some(moves)
+    }
+
+    /// Plays the given moves in order, setting up the next round whenever a round ends.
+    /// Will error with the first illegal move, in which case the moves before it remain played.
+    pub fn play_moves(&mut self, moves: &[Move]) -> Result<(), IllegalMoveError> {
+        for choice in moves {
+            self.make_move(choice)?;
+            if self.round_over() {
+                self.setup_next_round();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the valid moves of the active player grouped by the bowl they take from, as pairs of
+    /// the bowl index and its moves. Bowls are listed in ascending order, skipping any without
+    /// valid moves, and each bowl's moves keep the canonical order of [`GameState::get_valid_moves`].
+    /// Bowls with identical contents each get their own group, in index order.
+    pub fn legal_moves_by_bowl(&self) -> Vec<(usize, Vec<Move>)> {
+        let mut groups: Vec<(usize, Vec<Move>)> = Vec::new();
+        for choice in self.get_valid_moves() {
+            match groups.last_mut() {
+                Some((bowl, moves)) if *bowl == choice.bowl => moves.push(choice),
+                _ => groups.push((choice.bowl, vec![choice])),
+            }
+        }
+        groups
+    }
+
+    /// Returns the number of valid moves of the active player which take from each bowl, indexed
+    /// by bowl. Useful for ordering moves, as bowls with fewer options can be searched first.
+    pub fn move_counts_per_bowl(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.bowls.len()];
+        for choice in self.get_valid_moves() {
+            counts[choice.bowl] += 1;
+        }
+        counts
+    }
+
+    /// Returns true if the given move may be played by the active player in the current gamestate.
+    pub fn is_legal(&self, choice: &Move) -> bool {
+        if let Some(moves) = self.move_cache.get() {
+            return moves.contains(choice);
+        }
+        self.check_move(choice).is_ok()
+    }
+
+    /// Checks whether the given move may be played by the active player in the current gamestate.
+    /// Will error with the reason the move is illegal if the given move is illegal.
+    pub fn check_move(&self, choice: &Move) -> Result<(), IllegalMoveReason> {
+        let bowl = self
+            .bowls
+            .get(choice.bowl)
+            .ok_or(IllegalMoveReason::NoSuchBowl)?;
+        if !bowl.tiles().contains(&choice.tile_type) {
+            return Err(IllegalMoveReason::ColorNotInBowl);
+        }
+        self.boards
+            .get(self.active_player)
+            .ok_or(IllegalMoveReason::NoSuchPlayer)?
+            .check_hold(choice.tile_type, choice.row)
     }
 
     /// Makes a move, modifying the current gamestate.
-    /// Will error if the given move is illegal.
+    /// Will error if the given move is illegal, with an error holding both the move and the reason
+    /// it is illegal.
     pub fn make_move(&mut self, choice: &Move) -> Result<(), IllegalMoveError> {
-        let valid_moves = self.get_valid_moves();
-        if !valid_moves.contains(choice) {
-            return Err(IllegalMoveError);
-        }
+        self.make_move_checked(choice)
+            .map_err(|reason| IllegalMoveError {
+                choice: choice.clone(),
+                reason,
+            })
+    }
+
+    /// Returns a copy of this gamestate with the given move made, leaving this gamestate
+    /// unmodified. Will error in the same cases as [`GameState::make_move`].
+    pub fn with_move(&self, choice: &Move) -> Result<GameState, IllegalMoveError> {
+        let mut next = self.clone();
+        next.make_move(choice)?;
+        Ok(next)
+    }
+
+    /// Makes a move, modifying the current gamestate.
+    /// Will error with the reason the move is illegal if the given move is illegal, in which
+    /// case the gamestate is left unmodified.
+    pub fn make_move_checked(&mut self, choice: &Move) -> Result<(), IllegalMoveReason> {
+        self.make_move_detailed(choice).map(|_| ())
+    }
 
-        // Get the tiles and update the bowls
+    /// Makes a move, modifying the current gamestate, and reports where the taken tiles went.
+    /// Will error with the reason the move is illegal if the given move is illegal, in which
+    /// case the gamestate is left unmodified.
+    pub fn make_move_detailed(&mut self, choice: &Move) -> Result<MoveReport, IllegalMoveReason> {
+        self.check_move(choice)?;
+        self.move_cache.take();
+
+        // Get the tiles and update the bowls. Taking nothing is never a legal move, but the check
+        // above already rejects colours missing from the bowl, so at least one tile is taken
         let tiles = self
             .bowls
             .get_mut(choice.bowl)
-            .ok_or(IllegalMoveError)?
+            .expect("Invalid bowl")
             .take_tiles(choice.tile_type);
 
-        // A penalty is given if we're the first player to pick from the centre
-        let penalty = if choice.bowl == CENTRE_BOWL_IDX && self.first_token_owner.is_none() {
-            self.first_token_owner = Some(self.active_player);
-            1
-        } else {
-            0
+        let penalty = match choice.source() {
+            TileSource::Centre => self.take_first_token(),
+            TileSource::Bowl(_) => 0,
         };
 
         // Put the tiles into the appropriate row
-        let active_board = self
-            .boards
-            .get_mut(self.active_player)
-            .expect("Invalid player");
-        active_board.hold_tiles(choice.tile_type, tiles.0.len(), choice.row, penalty)?;
+        let to_row = self
+            .active_board_mut()
+            .hold_tiles(choice.tile_type, tiles.0.len(), choice.row, penalty)
+            .expect("Move was validated");
+        let mut report = MoveReport {
+            taken: tiles.0.len(),
+            to_row,
+            to_floor: tiles.0.len() - to_row,
+            to_centre: tiles.1.len(),
+            ..Default::default()
+        };
 
-        // Move the remaining tiles to the centre
-        self.bowls
-            .get_mut(CENTRE_BOWL_IDX)
-            .expect("Invalid bowl")
-            .extend(&tiles.1);
+        // Tiles dropped to the floor leave play, and the remaining tiles move to the centre
+        self.discard
+            .extend(std::iter::repeat_n(choice.tile_type, report.to_floor));
+        self.centre_mut().extend(&tiles.1);
+
+        self.move_count += 1;
+        if self.record_history {
+            self.history.push(choice.clone());
+        }
 
         // Cycle to the next player's turn
         self.active_player += 1;
-        if self.active_player >= self.boards.len() {
+        if self.active_player >= self.player_count() {
             self.active_player = 0;
         }
-        Ok(())
+        report.next_player = self.active_player;
+        report.round_ended = self.round_over();
+        self.debug_assert_tile_conservation();
+        Ok(report)
     }
 
-    /// Returns true if all bowls are empty, otherwise false.
-    pub fn round_over(&self) -> bool {
-        self.bowls.iter().all(|b| b.get_tile_types().is_empty())
+    /// Returns each hold row of the given player's board that is one tile short of being complete,
+    /// paired with the type of tile that would complete it.
+    /// Completing a row causes a tile to be placed and scored at the end of the round, so these
+    /// are the tiles an opponent may wish to deny.
+    ///
+    /// An empty first row is completed by any single tile, so it is reported once for each tile
+    /// type which could legally be held there.
+    pub fn completing_threats(&self, player: usize) -> Vec<(Row, Tile)> {
+        let board = self.boards.get(player).expect("Invalid player");
+        let mut threats = Vec::new();
+        for (row_idx, hold) in board.holds().iter().enumerate() {
+            let row = Row::Wall(row_idx);
+            let held: Vec<_> = hold.iter().flatten().collect();
+            if held.len() != row_idx {
+                continue;
+            }
+            match held.first() {
+                Some(&&tile_type) => threats.push((row, tile_type)),
+                None => threats.extend(
+                    tile_types()
+                        .filter(|&t| board.can_hold(t, row))
+                        .map(|t| (row, t)),
+                ),
+            }
+        }
+        threats
     }
 
-    /// Returns true if any player has completed a horizontal line on their board.
-    pub fn is_game_over(&self) -> bool {
-        self.boards.iter().any(|b| b.count_horizontal_lines() > 0)
+    /// Returns true if the given move takes tiles of a type which an opponent of the active player
+    /// needs to complete one of their rows this round, as found by
+    /// [`GameState::completing_threats`]. Such moves deny those tiles, whether or not they help
+    /// the active player.
+    ///
+    /// An empty first row is completed by a single tile of nearly any type, so it can't be denied
+    /// and is ignored. Returns false if the move's source holds no tiles of its type.
+    pub fn is_denial_move(&self, m: &Move) -> bool {
+        let takes_tiles = self
+            .bowls
+            .get(m.bowl)
+            .is_some_and(|b| b.tiles().contains(&m.tile_type));
+        takes_tiles
+            && (0..self.player_count())
+                .filter(|&p| p != self.active_player)
+                .any(|p| {
+                    self.completing_threats(p)
+                        .iter()
+                        .any(|&(row, t)| row != Row::Wall(0) && t == m.tile_type)
+                })
     }
 
-    /// Gets the index of the board with the highest score.
-    /// In the case of a tie, the number of horizontal lines are used.
-    /// If there is still a tie, the lower-indexed player will be returned.  
-    pub fn get_winner(&self) -> usize {
+    /// Returns true if the given player could still collect the bonus for placing every tile of
+    /// the given type, given the tiles of that type which remain available.
+    ///
+    /// Tiles placed on any wall never leave it, so only the tiles of that type which are not yet
+    /// placed can complete the bonus. Discarded tiles are counted as available, since the bag is
+    /// restocked from the discard once it runs out.
+    pub fn color_bonus_reachable(&self, player: usize, tile_type: Tile) -> bool {
+        let board = self.boards.get(player).expect("Invalid player");
+        if board
+            .bonuses()
+            .tile_types
+            .get(tile_type)
+            .copied()
+            .unwrap_or(false)
+        {
+            return true;
+        }
+        let on_wall = |b: &Board| {
+            b.placed()
+                .iter()
+                .flatten()
+                .filter(|&&t| t == Some(tile_type))
+                .count()
+        };
+        if self.is_game_over() {
+            return false;
+        }
+        let needed = BOARD_DIMENSION - on_wall(board);
+        let placed: usize = self.boards.iter().map(on_wall).sum();
+        needed <= self.tile_count(tile_type) - placed
+    }
+
+    /// Counts every tile of the given type in the game, wherever it is.
+    fn tile_count(&self, tile_type: Tile) -> usize {
+        self.bag
+            .items()
+            .iter()
+            .chain(self.bowls.iter().flat_map(|b| b.tiles()))
+            .chain(self.discard.iter())
+            .copied()
+            .chain(self.boards.iter().flat_map(|b| b.get_active_tiles()))
+            .filter(|&t| t == tile_type)
+            .count()
+    }
+
+    /// Suggests the move which leaves the active player's board with the best evaluation,
+    /// according to [`Board::evaluate`] with the default [`EvalWeights`].
+    /// In the case of a tie, the earliest such move from [`GameState::get_valid_moves`] is returned.
+    /// Returns `None` only if there are no valid moves.
+    pub fn suggest_move(&self) -> Option<Move> {
+        let player = self.active_player;
+        let mut best: Option<(Move, f32)> = None;
+        for choice in self.get_valid_moves() {
+            let mut next = self.clone();
+            if next.make_move(&choice).is_err() {
+                continue;
+            }
+            let eval = next.boards[player].evaluate(&EvalWeights::default());
+            if best.as_ref().is_none_or(|(_, best_eval)| eval > *best_eval) {
+                best = Some((choice, eval));
+            }
+        }
+        best.map(|(choice, _)| choice)
+    }
+
+    /// Estimates the value to the active player of taking the tiles of the given type from the bowl
+    /// at the given index, by Monte Carlo sampling. The tiles are held in the row suggested by
+    /// [`Board::best_row_for`], or dropped to the floor if no wall row may hold them.
+    ///
+    /// Each of the `rng_samples` samples plays the rest of the round with uniformly random moves,
+    /// and then scores it. The estimate is the active player's average change in score across the
+    /// samples, so the playouts never look beyond the end of the current round.
+    /// The playouts are seeded from this game's seed, or from zero if it isn't seeded, so the same
+    /// position always gives the same estimate.
+    /// Returns negative infinity if the tiles can't be taken, so an illegal take is never
+    /// preferred, and zero if no samples are requested.
+    pub fn expected_take_value(&self, bowl: usize, tile_type: Tile, rng_samples: usize) -> f32 {
+        let player = self.active_player;
+        let Some(board) = self.boards.get(player) else {
+            return f32::NEG_INFINITY;
+        };
+        let choice = Move {
+            bowl,
+            tile_type,
+            row: board.best_row_for(tile_type).unwrap_or(Row::Floor),
+        };
+        let Ok(after) = self.with_move(&choice) else {
+            return f32::NEG_INFINITY;
+        };
+
+        let mut rng = StdRng::seed_from_u64(self.seed.unwrap_or_default());
+        let mut total = 0.;
+        for _ in 0..rng_samples {
+            let mut state = after.clone();
+            while !state.round_over() {
+                let moves = state.get_valid_moves();
+                let choice = moves.choose(&mut rng).expect("No valid moves");
+                state.make_move(choice).expect("Generated move was illegal");
+            }
+            state.score_round();
+            total += state.boards[player].get_score() as f32 - board.get_score() as f32;
+        }
+        total / rng_samples.max(1) as f32
+    }
+
+    /// Returns a rough estimate of each player's chance of winning, in player order, summing to 1.
+    ///
+    /// This is only a heuristic, meant for showing live odds rather than for search. Each board is
+    /// evaluated with [`Board::evaluate`] using the default [`EvalWeights`], which accounts for the
+    /// score along with progress towards bonuses, and the evaluations are compared with a
+    /// logistic (softmax) curve. The curve flattens as more tiles remain off the boards, since
+    /// a lead early in the game is less certain. Once the game is over, the winners share the
+    /// estimate evenly.
+    pub fn win_estimate(&self) -> Vec<f32> {
+        // How many points of lead are needed to be counted as a likely win, from a full game
+        // left to be played to none at all
+        const OPENING_SPREAD: f32 = 20.;
+        const ENDGAME_SPREAD: f32 = 2.;
+
+        if self.is_game_over() {
+            let winners = self.winners();
+            return (0..self.boards.len())
+                .map(|p| {
+                    if winners.contains(&p) {
+                        1. / winners.len() as f32
+                    } else {
+                        0.
+                    }
+                })
+                .collect();
+        }
+
+        let total = self.rules.tileset().len();
+        let on_boards: usize = self
+            .boards
+            .iter()
+            .map(|b| b.get_active_tiles().count())
+            .sum();
+        let remaining = total.saturating_sub(on_boards) as f32 / total.max(1) as f32;
+        let spread = ENDGAME_SPREAD + (OPENING_SPREAD - ENDGAME_SPREAD) * remaining;
+
+        let evals: Vec<f32> = self
+            .boards
+            .iter()
+            .map(|b| b.evaluate(&EvalWeights::default()))
+            .collect();
+        // Shifting by the best evaluation keeps the exponentials from overflowing
+        let best = evals.iter().copied().fold(f32::MIN, f32::max);
+        let weights: Vec<f32> = evals.iter().map(|e| ((e - best) / spread).exp()).collect();
+        let sum: f32 = weights.iter().sum();
+        weights.into_iter().map(|w| w / sum).collect()
+    }
+
+    /// Returns the reward earned by the given player between `before` and this gamestate, which
+    /// should directly follow it. See [`GameState::step_reward_with_bonus`] for details.
+    /// The default [`WIN_REWARD`] is used as the terminal bonus.
+    pub fn step_reward(&self, before: &GameState, player: usize) -> f32 {
+        self.step_reward_with_bonus(before, player, WIN_REWARD)
+    }
+
+    /// Returns the reward earned by the given player between `before` and this gamestate, which
+    /// should directly follow it.
+    ///
+    /// The reward is the player's score in this gamestate minus their score in `before`, so gaining
+    /// points gives a positive reward and losing points to penalties gives a negative reward.
+    /// If this step ended the game and the player is among the winners, `win_bonus` is added.
+    pub fn step_reward_with_bonus(&self, before: &GameState, player: usize, win_bonus: f32) -> f32 {
+        let score = |state: &GameState| {
+            state
+                .boards
+                .get(player)
+                .expect("Invalid player")
+                .get_score() as f32
+        };
+        let mut reward = score(self) - score(before);
+        if self.is_game_over() && !before.is_game_over() && self.winners().contains(&player) {
+            reward += win_bonus;
+        }
+        reward
+    }
+
+    /// Plays this game out to completion, starting new rounds as required, and returns the winner.
+    /// Each move is chosen by the given policy, which picks the index of the move to play from the
+    /// legal moves, see [`Policy`].
+    ///
+    /// Returns `None` if the game can't be finished because no tiles remain to be dealt, see
+    /// [`GameState::is_deadlocked`].
+    pub fn policy_playout(&mut self, policy: &Policy) -> Option<usize> {
+        self.playout_with(|state, moves| policy(state, moves))
+    }
+
+    /// Plays this game out to completion as with [`GameState::policy_playout`], choosing each
+    /// move uniformly at random using `rng`.
+    pub fn random_playout(&mut self, rng: &mut impl Rng) -> Option<usize> {
+        self.playout_with(|_, moves| rng.random_range(0..moves.len()))
+    }
+
+    fn playout_with(
+        &mut self,
+        mut choose: impl FnMut(&GameState, &[Move]) -> usize,
+    ) -> Option<usize> {
+        while !self.is_game_over() {
+            if self.is_deadlocked() {
+                return None;
+            }
+            if self.round_over() {
+                self.setup_next_round();
+                continue;
+            }
+
+            let moves = self.get_valid_moves();
+            let choice = moves
+                .get(choose(self, &moves))
+                .expect("Policy chose an invalid move");
+            self.make_move(choice).expect("Generated move was illegal");
+        }
+        Some(self.get_winner())
+    }
+
+    /// Plays out the rest of the current round, with each move chosen by the given policy, and
+    /// then scores it with [`GameState::score_round`]. The next round is not dealt, so
+    /// [`GameState::deal_round`] should be called to continue the game.
+    ///
+    /// Returns a report of what each board scored, in player order.
+    pub fn simulate_round(&mut self, policy: &Policy) -> Vec<ScoreReport> {
+        while !self.round_over() {
+            let moves = self.get_valid_moves();
+            let choice = moves
+                .get(policy(self, &moves))
+                .expect("Policy chose an invalid move");
+            self.make_move(choice).expect("Generated move was illegal");
+        }
+        self.score_round()
+    }
+
+    /// Counts the number of move sequences of the given depth playable from this gamestate.
+    /// Moves are only counted within the current round; a line that finishes the round before
+    /// reaching the given depth does not contribute to the count.
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut count = 0;
+        for choice in self.get_valid_moves() {
+            let mut next = self.clone();
+            next.make_move(&choice).expect("Generated move was illegal");
+            count += next.perft(depth - 1);
+        }
+        count
+    }
+
+    /// Equivalent to [`GameState::perft`], but splits the moves of the first ply as evenly as
+    /// possible across the given number of threads.
+    pub fn perft_parallel(&self, depth: usize, threads: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.get_valid_moves();
+        let chunk_size = moves.len().div_ceil(threads.max(1)).max(1);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = moves
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|choice| {
+                                let mut next = self.clone();
+                                next.make_move(choice).expect("Generated move was illegal");
+                                next.perft(depth - 1)
+                            })
+                            .sum::<u64>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("Perft thread panicked"))
+                .sum()
+        })
+    }
+
+    /// Returns an iterator over the current score of each player, in player order.
+    pub fn scores(&self) -> impl Iterator<Item = usize> + '_ {
+        self.boards.iter().map(|b| b.get_score())
+    }
+
+    /// Returns a terse summary of every player's board for logs, with a line per player in the
+    /// form `P<player> score=<score> pen=<penalties> lines=<lines>`.
+    /// See [`Board::summary_line`] for details.
+    pub fn summary_lines(&self) -> String {
         self.boards
             .iter()
             .enumerate()
-            .max_by_key(|(_, b)| (b.get_score(), b.count_horizontal_lines()))
-            .unwrap()
-            .0
+            .map(|(i, b)| format!("P{} {}", i, b.summary_line()))
+            .collect::<Vec<_>>()
+            .join("\n")
     }
-}
 
-#[derive(Default)]
-pub struct GameStateBuilder {
-    active_player: usize,
-    boards: Vec<Board>,
-    bowls: Vec<Bowl>,
-    bag: Bag<Tile>,
-    first_token_owner: Option<usize>,
-}
+    /// Returns the centre area, which is stored first among the bowls.
+    pub fn centre(&self) -> &Bowl {
+        self.bowls.get(CENTRE_BOWL_IDX).expect("Missing centre")
+    }
 
-impl GameStateBuilder {
-    pub fn active_player(mut self, active_player: usize) -> Self {
-        self.active_player = active_player;
-        self
+    /// Returns the index of every bowl, including the centre, holding at least one tile of the
+    /// given type, paired with the number of such tiles in that bowl.
+    pub fn bowls_with(&self, tile_type: Tile) -> Vec<(usize, usize)> {
+        self.bowls
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (i, b.tiles().iter().filter(|&&t| t == tile_type).count()))
+            .filter(|&(_, count)| count > 0)
+            .collect()
     }
 
-    pub fn boards(mut self, boards: Vec<Board>) -> Self {
-        self.boards = boards;
-        self
+    /// Returns every tile type which can still be taken this round, from any bowl including the
+    /// centre, in ascending order without duplicates.
+    pub fn available_tile_types(&self) -> Vec<Tile> {
+        let mut tile_types: Vec<_> = self.bowls.iter().flat_map(|b| b.get_tile_types()).collect();
+        tile_types.sort();
+        tile_types.dedup();
+        tile_types
     }
 
-    pub fn bowls(mut self, bowls: Vec<Bowl>) -> Self {
-        self.bowls = bowls;
-        self
+    /// Returns every tile type the active player may take from the bowl at the given index, in
+    /// ascending order without duplicates. Since tiles may always be dropped to the floor, these are
+    /// exactly the tile types in the bowl. Returns an empty list if there is no such bowl.
+    pub fn takeable_colors(&self, bowl: usize) -> Vec<Tile> {
+        self.bowls
+            .get(bowl)
+            .map(|b| b.get_tile_types())
+            .unwrap_or_default()
     }
 
-    pub fn bag(mut self, bag: Bag<Tile>) -> Self {
-        self.bag = bag;
-        self
+    /// Returns the number of tiles remaining this round, across every bowl including the centre.
+    pub fn tiles_in_bowls(&self) -> usize {
+        self.bowls.iter().map(|b| b.tiles().len()).sum()
     }
 
-    pub fn first_token_owner(mut self, first_token_owner: Option<usize>) -> Self {
-        self.first_token_owner = first_token_owner;
-        self
+    /// Returns the number of tiles in the centre.
+    pub fn tiles_in_centre(&self) -> usize {
+        self.centre().tiles().len()
     }
 
-    pub fn build(self) -> GameState {
-        GameState {
-            active_player: self.active_player,
-            boards: self.boards,
-            bowls: self.bowls,
-            bag: self.bag,
-            first_token_owner: self.first_token_owner,
+    fn centre_mut(&mut self) -> &mut Bowl {
+        self.bowls.get_mut(CENTRE_BOWL_IDX).expect("Missing centre")
+    }
+
+    /// Returns true if tiles have already been taken from the centre this round.
+    /// The first player token is taken along with the first tiles taken from the centre, so this
+    /// is the case exactly when the token has an owner, and the next centre take has no penalty.
+    pub fn centre_taken(&self) -> bool {
+        self.first_token_owner.is_some()
+    }
+
+    /// Returns true if the first player token is still in the centre this round, to be taken by
+    /// the next player to take from the centre. This is the opposite of
+    /// [`GameState::centre_taken`], and becomes true again when the next round is set up.
+    pub fn first_token_available(&self) -> bool {
+        !self.centre_taken()
+    }
+
+    /// Returns how many tiles taking the given tile type from the given bowl would yield, and how
+    /// many of the remaining tiles would be moved to the centre, without modifying this gamestate.
+    /// Tiles left behind in the centre stay where they are, so taking from the centre never moves
+    /// any tiles. A take which yields nothing, including from a bowl which doesn't exist, is not
+    /// legal and moves nothing.
+    pub fn preview_take(&self, bowl: usize, tile_type: Tile) -> (usize, usize) {
+        let Some(tiles) = self.bowls.get(bowl).map(|b| b.tiles()) else {
+            return (0, 0);
+        };
+        let taken = tiles.iter().filter(|&&t| t == tile_type).count();
+        match TileSource::from_bowl_index(bowl) {
+            TileSource::Bowl(_) if taken > 0 => (taken, tiles.len() - taken),
+            _ => (taken, 0),
+        }
+    }
+
+    /// Returns the penalty, in tiles, for taking from the given bowl, which is the rules' centre
+    /// penalty if the take would claim the first player token from the centre, otherwise 0.
+    /// Taking from a regular bowl, or from the centre once it has already been taken from this
+    /// round, incurs no penalty.
+    pub fn centre_penalty_for(&self, bowl: usize) -> usize {
+        match TileSource::from_bowl_index(bowl) {
+            TileSource::Centre if self.first_token_available() => self.rules.centre_penalty,
+            _ => 0,
+        }
+    }
+
+    /// Gives the first player token to the active player if it is still in the centre, returning
+    /// the number of penalty tiles incurred by taking it.
+    fn take_first_token(&mut self) -> usize {
+        if self.first_token_owner.is_some() {
+            return 0;
+        }
+        self.first_token_owner = Some(self.active_player);
+        self.rules.centre_penalty
+    }
+
+    /// Returns true if no board gained any wall tiles in the round most recently scored by
+    /// [`GameState::setup_next_round`], meaning every tile taken that round went to the floor or
+    /// was left incomplete in a hold. Returns false if no round has been scored yet.
+    pub fn round_is_all_penalty(&self) -> bool {
+        !self.last_round_placements.is_empty() && self.last_round_placements.iter().all(|&p| p == 0)
+    }
+
+    /// Returns true if all bowls are empty, otherwise false.
+    pub fn round_over(&self) -> bool {
+        self.bowls.iter().all(|b| b.get_tile_types().is_empty())
+    }
+
+    /// Returns true if the game cannot progress: the round is over and the game is not, but no
+    /// tiles are left anywhere to deal for the next round. This can't happen in a standard game,
+    /// but is reachable from crafted positions, where drivers should stop rather than
+    /// repeatedly setting up empty rounds.
+    /// Tiles returned to the discard by completed holds are taken into account, as are lines which
+    /// those holds would complete.
+    pub fn is_deadlocked(&self) -> bool {
+        if !self.round_over() || self.is_game_over() {
+            return false;
+        }
+        if !self.bag.items().is_empty() || !self.discard.is_empty() {
+            return false;
+        }
+        self.boards.iter().all(|board| {
+            let mut board = *board;
+            score_board(&mut board, &self.rules).discarded.is_empty()
+                && board.count_horizontal_lines() < self.rules.end_on_lines
+        })
+    }
+
+    /// Returns true if enough tiles remain to completely fill at least one bowl when the next
+    /// round is dealt, counting the tiles in the bag and the discard, along with any tiles which
+    /// will be discarded before the deal: those left in the bowls, and those freed by scoring the
+    /// boards' holds. This is independent of [`GameState::is_game_over`], which only considers
+    /// completed lines, so drivers can stop gracefully once the tiles run out.
+    pub fn can_deal_next_round(&self) -> bool {
+        let to_discard: usize = self
+            .boards
+            .iter()
+            .map(|board| {
+                let mut board = *board;
+                score_board(&mut board, &self.rules).discarded.len()
+            })
+            .sum();
+        let left_in_bowls: usize = self.bowls.iter().map(|b| b.tiles().len()).sum();
+        self.bag.items().len() + self.discard.len() + left_in_bowls + to_discard
+            >= self.rules.bowl_capacity
+    }
+
+    /// Returns true if any player has completed the number of horizontal lines on their board
+    /// required by the rules, which is a single line in the standard game.
+    /// Solo games additionally end once their fixed number of rounds have been played.
+    pub fn is_game_over(&self) -> bool {
+        if self.is_solo() && self.round > SOLO_ROUNDS {
+            return true;
+        }
+        self.boards
+            .iter()
+            .any(|b| b.count_horizontal_lines() >= self.rules.end_on_lines)
+    }
+
+    /// Returns the number of players in this game.
+    pub fn player_count(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// Returns true if this is a solo game, with only a single player.
+    pub fn is_solo(&self) -> bool {
+        self.player_count() == 1
+    }
+
+    /// Returns the score of the only player in a solo game, or `None` if this is not a solo game.
+    /// The objective of a solo game is to maximize this score within its fixed number of rounds.
+    pub fn solo_score(&self) -> Option<usize> {
+        match self.boards.as_slice() {
+            [board] => Some(board.get_score()),
+            _ => None,
+        }
+    }
+
+    /// Returns the score each player would finish with if the game ended right now, in player order.
+    /// Each board's completed holds are placed and scored, along with any bonuses they complete and
+    /// the pending floor penalties, without modifying this gamestate.
+    pub fn final_scores_now(&self) -> Vec<usize> {
+        self.boards
+            .iter()
+            .map(|board| {
+                let mut board = *board;
+                board.place_holds();
+                board.get_score()
+            })
+            .collect()
+    }
+
+    /// Gets the indices of every player who shares the victory, in ascending order.
+    /// Following the official rules, the highest score wins, with ties broken by the number of
+    /// completed horizontal lines. If there is still a tie, the victory is shared.
+    pub fn winners(&self) -> Vec<usize> {
+        let key = |b: &Board| (b.get_score(), b.count_horizontal_lines());
+        let best = self.boards.iter().map(key).max().expect("No players");
+        self.boards
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| key(b) == best)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Gets the result of this game according to [`GameState::winners`].
+    pub fn result(&self) -> GameResult {
+        match self.winners().as_slice() {
+            [winner] => GameResult::Win(*winner),
+            winners => GameResult::Draw(winners.to_vec()),
+        }
+    }
+
+    /// Gets the index of the board with the highest score.
+    /// In the case of a tie, the number of horizontal lines are used.
+    /// If there is still a tie, the lower-indexed player will be returned.
+    pub fn get_winner(&self) -> usize {
+        self.winners()[0]
+    }
+}
+
+/// Gamestates are equal when they are in the same position, as in [`GameState::position_eq`], with
+/// the same player to move, the same tiles in the bag in the same order, the same discard, and the
+/// same counters, seed, and history. Only the game itself is compared, so the move cache, whether
+/// history is recorded, and the rules are not.
+impl PartialEq for GameState {
+    fn eq(&self, other: &Self) -> bool {
+        self.position_eq(other)
+            && self.active_player == other.active_player
+            && self.bag.items() == other.bag.items()
+            && self.discard == other.discard
+            && self.round_starter == other.round_starter
+            && self.move_count == other.move_count
+            && self.round == other.round
+            && self.last_setup_was_partial == other.last_setup_was_partial
+            && self.last_round_placements == other.last_round_placements
+            && self.seed == other.seed
+            && self.players_swapped == other.players_swapped
+            && self.history == other.history
+    }
+}
+
+/// Picks the player who starts the first round under the given rules. If the rules randomize the
+/// start, the pick is made from the seed when given, so that seeded games are reproducible.
+/// The seed drives a separate generator from the bag's, so the deal is unaffected by the pick.
+fn starting_player(players: usize, rules: &RulesConfig, seed: Option<u64>) -> usize {
+    if !rules.randomize_start || players == 0 {
+        return 0;
+    }
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed).random_range(0..players),
+        None => rand::rng().random_range(0..players),
+    }
+}
+
+/// Scores a board at the end of a round under the given rules, clearing any incomplete holds if
+/// the rules don't carry them into the next round.
+fn score_board(board: &mut Board, rules: &RulesConfig) -> ScoreReport {
+    let mut report = board.place_holds();
+    if !rules.carry_incomplete_holds {
+        report.discarded.extend(board.clear_holds());
+    }
+    report
+}
+
+/// Describes why the components given to a [`GameStateBuilder`] do not form a valid gamestate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvalidGameStateError {
+    /// No boards were given.
+    NoPlayers,
+    /// The number of bowls, including the centre, does not match the rules for the number of boards.
+    BowlCount { expected: usize, found: usize },
+    /// The active player does not have a board.
+    ActivePlayerOutOfRange,
+    /// The owner of the first player token does not have a board.
+    FirstTokenOwnerOutOfRange,
+    /// The player who started the round does not have a board.
+    RoundStarterOutOfRange,
+    /// A tile is not of any tile type.
+    UnknownTileType,
+    /// There are more tiles of the given type in play than in the rules' tileset.
+    TooManyTiles { tile_type: Tile },
+}
+
+#[derive(Default)]
+pub struct GameStateBuilder {
+    active_player: usize,
+    boards: Vec<Board>,
+    bowls: Vec<Bowl>,
+    bag: Bag<Tile>,
+    first_token_owner: Option<usize>,
+    round_starter: usize,
+    move_count: usize,
+    round: usize,
+    seed: Option<u64>,
+    rules: RulesConfig,
+}
+
+impl GameStateBuilder {
+    pub fn active_player(mut self, active_player: usize) -> Self {
+        self.active_player = active_player;
+        self
+    }
+
+    pub fn boards(mut self, boards: Vec<Board>) -> Self {
+        self.boards = boards;
+        self
+    }
+
+    pub fn bowls(mut self, bowls: Vec<Bowl>) -> Self {
+        self.bowls = bowls;
+        self
+    }
+
+    /// Sets the bag, which is drawn from as is, so a bag from [`Bag::new_ordered`] controls the
+    /// exact order of every draw until the bag must be restocked from the discard.
+    pub fn bag(mut self, bag: Bag<Tile>) -> Self {
+        self.bag = bag;
+        self
+    }
+
+    pub fn first_token_owner(mut self, first_token_owner: Option<usize>) -> Self {
+        self.first_token_owner = first_token_owner;
+        self
+    }
+
+    /// Sets the player who started the current round, who starts the next round as well if
+    /// nobody takes the first player token. Defaults to player 0.
+    pub fn round_starter(mut self, round_starter: usize) -> Self {
+        self.round_starter = round_starter;
+        self
+    }
+
+    pub fn move_count(mut self, move_count: usize) -> Self {
+        self.move_count = move_count;
+        self
+    }
+
+    pub fn round(mut self, round: usize) -> Self {
+        self.round = round;
+        self
+    }
+
+    /// Sets the seed recorded for the gamestate, see [`GameState::seed`].
+    /// The bag is not reseeded, so it should already be seeded with it.
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn rules(mut self, rules: RulesConfig) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Builds the gamestate after checking that its components are consistent with each other
+    /// and with the rules, see [`GameStateBuilder::validate`].
+    /// See [`GameStateBuilder::build`] for how the discard is determined.
+    pub fn try_build(self) -> Result<GameState, InvalidGameStateError> {
+        self.validate()?;
+        Ok(self.build())
+    }
+
+    /// Checks that the components given to this builder are consistent with each other and with
+    /// the rules, without building the gamestate. Will error with the first inconsistency found.
+    pub fn validate(&self) -> Result<(), InvalidGameStateError> {
+        let players = self.boards.len();
+        if players == 0 {
+            return Err(InvalidGameStateError::NoPlayers);
+        }
+        let expected = self.rules.bowl_count(players);
+        if self.bowls.len() != expected {
+            return Err(InvalidGameStateError::BowlCount {
+                expected,
+                found: self.bowls.len(),
+            });
+        }
+        if self.active_player >= players {
+            return Err(InvalidGameStateError::ActivePlayerOutOfRange);
+        }
+        if self.first_token_owner.is_some_and(|p| p >= players) {
+            return Err(InvalidGameStateError::FirstTokenOwnerOutOfRange);
+        }
+        if self.round_starter >= players {
+            return Err(InvalidGameStateError::RoundStarterOutOfRange);
+        }
+        if self.tiles().any(|t| t >= TILE_TYPES) {
+            return Err(InvalidGameStateError::UnknownTileType);
+        }
+        if let Some(tile_type) =
+            tile_types().find(|&t| self.tiles_in_play(t) > self.rules.tiles_per_type)
+        {
+            return Err(InvalidGameStateError::TooManyTiles { tile_type });
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator over every tile given to this builder, in the bag, the bowls, or on
+    /// the boards.
+    fn tiles(&self) -> impl Iterator<Item = Tile> + '_ {
+        self.boards
+            .iter()
+            .flat_map(|b| b.get_active_tiles())
+            .chain(self.bowls.iter().flat_map(|b| b.tiles().iter().copied()))
+            .chain(self.bag.items().iter().copied())
+    }
+
+    /// Counts the tiles of the given type given to this builder.
+    fn tiles_in_play(&self, tile_type: Tile) -> usize {
+        self.tiles().filter(|&t| t == tile_type).count()
+    }
+
+    /// Builds the gamestate.
+    /// Since the discard is not tracked by the builder, any tiles of the rules' tileset which are
+    /// not in the bag, the bowls, or on the boards are assumed to have been discarded.
+    pub fn build(self) -> GameState {
+        let mut discard = Vec::new();
+        for tile_type in tile_types() {
+            discard.extend(std::iter::repeat_n(
+                tile_type,
+                self.rules
+                    .tiles_per_type
+                    .saturating_sub(self.tiles_in_play(tile_type)),
+            ));
+        }
+        GameState {
+            active_player: self.active_player,
+            boards: self.boards,
+            bowls: self.bowls,
+            bag: self.bag,
+            discard,
+            first_token_owner: self.first_token_owner,
+            round_starter: self.round_starter,
+            move_count: self.move_count,
+            round: self.round,
+            last_setup_was_partial: false,
+            last_round_placements: Vec::new(),
+            seed: self.seed,
+            players_swapped: false,
+            record_history: false,
+            history: Vec::new(),
+            cache_moves: false,
+            move_cache: OnceLock::new(),
+            rules: self.rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+    use super::*;
+    use crate::board::BonusEvent;
+    use crate::row::Row;
+
+    /// Builds a valid gamestate from the given boards, with the given tiles in the first bowls,
+    /// starting with the centre, and every other bowl empty. The bag is left empty.
+    fn state_with(boards: Vec<Board>, bowls: &[&[Tile]]) -> GameState {
+        let mut all_bowls = vec![Bowl::default(); RulesConfig::default().bowl_count(boards.len())];
+        for (bowl, tiles) in all_bowls.iter_mut().zip(bowls) {
+            bowl.fill(tiles.to_vec());
+        }
+        GameState::builder()
+            .boards(boards)
+            .bowls(all_bowls)
+            .try_build()
+            .unwrap()
+    }
+
+    /// Returns a board with a tile placed at each of the given wall positions.
+    fn board_with_placed(positions: &[(usize, usize)]) -> Board {
+        let mut placed = [[None; BOARD_DIMENSION]; BOARD_DIMENSION];
+        for &(row, col) in positions {
+            placed[row][col] = Some(Board::get_tile_type_at_pos(row, col));
+        }
+        Board::builder().placed(placed).build()
+    }
+
+    #[test]
+    fn illegal_moves_explain_why() {
+        let mut board = board_with_placed(&[(2, 2)]);
+        board.hold_tiles(2, 1, Row::Wall(1), 0).unwrap();
+        let mut state = state_with(vec![board, Board::default()], &[&[], &[0, 0, 1, 1]]);
+        let before = state.clone();
+
+        let cases = [
+            (6, 0, Row::Floor, IllegalMoveReason::NoSuchBowl),
+            (1, 3, Row::Floor, IllegalMoveReason::ColorNotInBowl),
+            (1, 0, Row::Wall(5), IllegalMoveReason::NoSuchRow),
+            (1, 0, Row::Wall(1), IllegalMoveReason::RowColorConflict),
+            (
+                1,
+                0,
+                Row::Wall(2),
+                IllegalMoveReason::RowAlreadyHasColorPlaced,
+            ),
+        ];
+        for (bowl, tile_type, row, reason) in cases {
+            let choice = Move {
+                bowl,
+                tile_type,
+                row,
+            };
+            assert_eq!(state.make_move_checked(&choice), Err(reason));
+            assert_eq!(state, before);
+        }
+
+        let mut orphaned = GameState::builder()
+            .active_player(2)
+            .boards(vec![Board::default(); 2])
+            .bowls(state.bowls().clone())
+            .build();
+        let choice = Move {
+            bowl: 1,
+            tile_type: 0,
+            row: Row::Floor,
+        };
+        assert_eq!(
+            orphaned.make_move_checked(&choice),
+            Err(IllegalMoveReason::NoSuchPlayer)
+        );
+    }
+
+    #[test]
+    fn threats_report_rows_one_tile_short() {
+        let mut board = Board::default();
+        board.hold_tiles(1, 2, Row::Wall(2), 0).unwrap();
+        board.hold_tiles(3, 1, Row::Wall(3), 0).unwrap();
+        let state = state_with(vec![Board::default(), board], &[]);
+
+        let threats = state.completing_threats(1);
+        let beyond_first: Vec<_> = threats
+            .iter()
+            .filter(|(row, _)| *row != Row::Wall(0))
+            .collect();
+        assert_eq!(beyond_first, [&(Row::Wall(2), 1)]);
+        assert_eq!(threats.len(), TILE_TYPES + 1);
+    }
+
+    #[test]
+    fn suggests_the_move_completing_a_row() {
+        let board = board_with_placed(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        let state = state_with(vec![board, Board::default()], &[&[], &[0, 3, 3, 3]]);
+
+        let completing = Move {
+            bowl: 1,
+            tile_type: 0,
+            row: Row::Wall(0),
+        };
+        assert_eq!(state.suggest_move(), Some(completing));
+    }
+
+    #[test]
+    fn moves_are_counted() {
+        let mut state = GameState::new(2);
+        state.setup_next_round();
+        assert_eq!(*state.move_count(), 0);
+        for played in 1..=3 {
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+            assert_eq!(*state.move_count(), played);
+        }
+
+        let illegal = Move {
+            bowl: state.bowls().len(),
+            ..Move::default()
+        };
+        assert!(state.make_move(&illegal).is_err());
+        assert_eq!(*state.move_count(), 3);
+    }
+
+    #[test]
+    fn scoring_placements_are_rewarded() {
+        let board = board_with_placed(&[(0, 1)]);
+        let before = state_with(vec![board, Board::default()], &[&[], &[0]]);
+
+        let mut after = before.clone();
+        let choice = Move {
+            bowl: 1,
+            tile_type: 0,
+            row: Row::Wall(0),
+        };
+        after.make_move(&choice).unwrap();
+        assert_eq!(after.step_reward(&before, 0), 0.);
+        after.setup_next_round();
+
+        assert_eq!(after.boards()[0].get_score(), 2);
+        assert_eq!(after.step_reward(&before, 0), 2.);
+        assert_eq!(after.step_reward(&before, 1), 0.);
+    }
+
+    #[test]
+    fn parallel_perft_matches_perft() {
+        let mut state = GameState::new(2);
+        state.setup_next_round();
+        assert_eq!(state.perft_parallel(2, 4), state.perft(2));
+        assert_eq!(
+            state.perft_parallel(1, 4),
+            state.get_valid_moves().len() as u64
+        );
+    }
+
+    #[test]
+    fn new_game_matches_fresh_game() {
+        let mut state = GameState::new_seeded(3, 5);
+        state.setup_next_round();
+        for _ in 0..4 {
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+        let bowls = state.bowls().as_ptr();
+
+        state.new_game(Some(9));
+        let mut fresh = GameState::new_seeded(3, 9);
+        fresh.setup_next_round();
+        assert!(state.boards().iter().all(|board| board.get_score() == 0));
+        for (bowl, fresh_bowl) in state.bowls().iter().zip(fresh.bowls()) {
+            assert_eq!(bowl.tiles(), fresh_bowl.tiles());
+        }
+        assert_eq!(state.bag().items(), fresh.bag().items());
+        assert_eq!(*state.move_count(), 0);
+        assert_eq!(*state.seed(), Some(9));
+        assert_eq!(state.bowls().as_ptr(), bowls);
+
+        state.new_game(None);
+        assert_eq!(*state.seed(), None);
+        assert_eq!(state.bag().items().len(), fresh.bag().items().len());
+    }
+
+    #[test]
+    fn history_replays_to_an_equal_state() {
+        let mut state = GameState::new_seeded(2, 15);
+        state.set_record_history(true);
+        state.setup_next_round();
+        for _ in 0..3 {
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+        assert_eq!(state.history().len(), 3);
+
+        assert_eq!(state.replay_from_start().unwrap(), state);
+        assert_eq!(GameState::new(2).replay_from_start(), None);
+    }
+
+    #[test]
+    fn solo_games_play_rounds_and_score() {
+        let mut state = GameState::new_seeded(1, 16);
+        state.setup_next_round();
+        assert!(state.is_solo());
+        assert_eq!(state.bowls().len(), 4);
+
+        let mut rng = StdRng::seed_from_u64(16);
+        while !state.round_over() {
+            let choice = state.get_valid_moves().choose(&mut rng).unwrap().clone();
+            state.make_move(&choice).unwrap();
+            assert_eq!(*state.active_player(), 0);
+        }
+        state.setup_next_round();
+        let score = state.solo_score().unwrap();
+        assert_eq!(score, state.boards()[0].get_score());
+        assert!(score <= state.boards()[0].max_achievable_score());
+
+        state.random_playout(&mut rng);
+        assert!(*state.round() <= SOLO_ROUNDS + 1);
+        assert_eq!(GameState::new(2).solo_score(), None);
+    }
+
+    #[test]
+    fn first_centre_take_claims_the_token_once() {
+        let mut state = state_with(vec![Board::default(); 2], &[&[0, 1], &[2, 2]]);
+        let take = |bowl, tile_type| Move {
+            bowl,
+            tile_type,
+            row: Row::Floor,
+        };
+
+        state.make_move(&take(0, 0)).unwrap();
+        assert_eq!(*state.first_token_owner(), Some(0));
+        assert_eq!(*state.boards()[0].penalties(), 2);
+
+        state.make_move(&take(0, 1)).unwrap();
+        assert_eq!(*state.first_token_owner(), Some(0));
+        assert_eq!(*state.boards()[1].penalties(), 1);
+
+        state.make_move(&take(1, 2)).unwrap();
+        state.setup_next_round();
+        assert_eq!(*state.first_token_owner(), None);
+        assert_eq!(*state.active_player(), 0);
+    }
+
+    #[test]
+    fn move_report_counts_overflow() {
+        let mut state = state_with(vec![Board::default(); 2], &[&[], &[3, 3, 3, 3]]);
+        let report = state
+            .make_move_detailed(&Move {
+                bowl: 1,
+                tile_type: 3,
+                row: Row::Wall(1),
+            })
+            .unwrap();
+
+        assert_eq!(report.taken, 4);
+        assert_eq!(report.to_row, 2);
+        assert_eq!(report.to_floor, 2);
+        assert_eq!(report.to_centre, 0);
+        assert_eq!(*state.boards()[0].penalties(), 2);
+    }
+
+    #[test]
+    fn valid_moves_for_active_player_match() {
+        let mut state = GameState::new_seeded(3, 17);
+        state.setup_next_round();
+        for _ in 0..4 {
+            let active = *state.active_player();
+            assert_eq!(state.valid_moves_for(active), state.get_valid_moves());
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+        assert_eq!(state.try_valid_moves_for(3), None);
+    }
+
+    #[test]
+    fn reduced_tileset_fills_bag() {
+        let rules = RulesConfig {
+            tiles_per_type: 10,
+            ..RulesConfig::default()
+        };
+        let state = GameState::with_rules(2, rules);
+        assert_eq!(state.bag().items().len(), 10 * BOARD_DIMENSION);
+        assert_eq!(state.bag().histogram(), [10; TILE_TYPES]);
+    }
+
+    #[test]
+    fn short_fills_are_flagged_and_played() {
+        let rules = RulesConfig {
+            tiles_per_type: 2,
+            ..RulesConfig::default()
+        };
+        let mut state = GameState::with_rules_seeded(2, rules, 22);
+        state.setup_next_round();
+        assert!(*state.last_setup_was_partial());
+        assert_eq!(state.tiles_in_bowls(), 2 * TILE_TYPES);
+
+        while !state.round_over() {
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+        state.setup_next_round();
+        assert_eq!(*state.round(), 2);
+
+        let mut full = GameState::new_seeded(2, 22);
+        full.setup_next_round();
+        assert!(!*full.last_setup_was_partial());
+    }
+
+    #[test]
+    fn seeded_deals_are_identical() {
+        let deal = |seed| {
+            let mut state = GameState::new_seeded(2, seed);
+            state.setup_next_round();
+            state
+        };
+        let (first, second) = (deal(7), deal(7));
+        assert_eq!(first.bowls(), second.bowls());
+        assert_eq!(first.bag().items(), second.bag().items());
+        assert_ne!(first.bowls(), deal(8).bowls());
+    }
+
+    #[test]
+    fn discarded_tiles_are_conserved() {
+        let mut state = GameState::new_seeded(3, 23);
+        state.setup_next_round();
+        let total = |state: &GameState| {
+            state.bag().items().len()
+                + state.tiles_in_bowls()
+                + state
+                    .boards()
+                    .iter()
+                    .map(|b| b.get_active_tiles().count())
+                    .sum::<usize>()
+                + state.discarded_total()
+        };
+
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut discarded = false;
+        while *state.round() < 4 {
+            if state.round_over() {
+                state.setup_next_round();
+            } else {
+                let choice = state.get_valid_moves().choose(&mut rng).unwrap().clone();
+                state.make_move(&choice).unwrap();
+            }
+            assert_eq!(total(&state), state.rules().tileset().len());
+            discarded |= state.discarded_total() > 0;
+        }
+        assert!(discarded);
+    }
+
+    #[test]
+    fn floor_only_rounds_are_flagged() {
+        let play_round = |row: Row| {
+            let mut state = GameState::builder()
+                .boards(vec![Board::default(); 2])
+                .bowls(vec![
+                    Bowl::default(),
+                    Bowl::from_tiles(vec![0]),
+                    Bowl::from_tiles(vec![1, 1]),
+                    Bowl::default(),
+                    Bowl::default(),
+                    Bowl::default(),
+                ])
+                .round(1)
+                .try_build()
+                .unwrap();
+            assert!(!state.round_is_all_penalty());
+            for (bowl, tile_type) in [(1, 0), (2, 1)] {
+                state
+                    .make_move(&Move {
+                        bowl,
+                        tile_type,
+                        row,
+                    })
+                    .unwrap();
+            }
+            state.setup_next_round();
+            state.round_is_all_penalty()
+        };
+        assert!(play_round(Row::Floor));
+        assert!(!play_round(Row::Wall(0)));
+    }
+
+    #[test]
+    fn empty_takes_are_rejected() {
+        let mut state = state_with(vec![Board::default(); 2], &[&[], &[1, 1, 2, 2]]);
+        let before = state.clone();
+        for (bowl, tile_type) in [(0, 1), (1, 0), (2, 1)] {
+            let choice = Move {
+                bowl,
+                tile_type,
+                row: Row::Floor,
+            };
+            assert_eq!(
+                state.check_move(&choice),
+                Err(IllegalMoveReason::ColorNotInBowl)
+            );
+            let error = state.make_move(&choice).unwrap_err();
+            assert_eq!(error.reason, IllegalMoveReason::ColorNotInBowl);
+            assert_eq!(state, before);
+        }
+    }
+
+    #[test]
+    fn valid_moves_follow_the_canonical_order() {
+        let mut board = board_with_placed(&[(0, 0)]);
+        board.hold_tiles(2, 1, Row::Wall(1), 0).unwrap();
+        let state = state_with(vec![board, Board::default()], &[&[3], &[1, 0]]);
+
+        let moves = |bowl, tile_type, rows: &[Row]| {
+            rows.iter()
+                .map(|&row| Move {
+                    bowl,
+                    tile_type,
+                    row,
+                })
+                .collect::<Vec<_>>()
+        };
+        let (w, f) = (Row::Wall, Row::Floor);
+        let expected = [
+            moves(0, 3, &[w(0), w(2), w(3), w(4), f]),
+            moves(1, 0, &[w(2), w(3), w(4), f]),
+            moves(1, 1, &[w(0), w(2), w(3), w(4), f]),
+        ]
+        .concat();
+        assert_eq!(state.get_valid_moves(), expected);
+    }
+
+    /// Picks the move whose tile would score the most if placed on the wall now.
+    fn greedy(state: &GameState, moves: &[Move]) -> usize {
+        let board = state.active_board();
+        (0..moves.len())
+            .max_by_key(|&i| {
+                board
+                    .score_preview(moves[i].tile_type, moves[i].row)
+                    .unwrap_or(0)
+            })
+            .expect("No valid moves")
+    }
+
+    #[test]
+    fn greedy_playout_completes() {
+        for seed in 0..4 {
+            let mut state = GameState::new_seeded(2, seed);
+            let winner = state.policy_playout(&greedy);
+            assert!(state.is_game_over());
+            assert_eq!(winner, Some(state.get_winner()));
+        }
+    }
+
+    #[test]
+    fn playouts_report_whether_they_finished() {
+        for seed in 0..4 {
+            let mut state = GameState::new_seeded(4, seed);
+            let winner = state.random_playout(&mut StdRng::seed_from_u64(seed));
+            assert!(state.is_game_over());
+            assert_eq!(winner, Some(state.get_winner()));
+        }
+
+        // Without a tileset, nothing can be dealt and the game can't be finished
+        let rules = RulesConfig {
+            tiles_per_type: 0,
+            ..RulesConfig::default()
+        };
+        let mut state = GameState::with_rules_seeded(2, rules, 0);
+        assert_eq!(state.random_playout(&mut StdRng::seed_from_u64(0)), None);
+        assert!(state.is_deadlocked());
+    }
+
+    #[test]
+    fn games_can_end_on_more_lines() {
+        let rules = RulesConfig {
+            end_on_lines: 2,
+            ..RulesConfig::default()
+        };
+        let row = |row| (0..BOARD_DIMENSION).map(move |col| (row, col));
+        let with_lines = |positions: Vec<(usize, usize)>| {
+            GameState::builder()
+                .boards(vec![board_with_placed(&positions), Board::default()])
+                .bowls(vec![Bowl::default(); rules.bowl_count(2)])
+                .rules(rules)
+                .try_build()
+                .unwrap()
+        };
+
+        let one_line = with_lines(row(0).collect());
+        assert!(!one_line.is_game_over());
+        assert!(state_with(one_line.boards().clone(), &[]).is_game_over());
+
+        let two_lines = with_lines(row(0).chain(row(3)).collect());
+        assert!(two_lines.is_game_over());
+    }
+
+    #[test]
+    fn ties_are_broken_by_completed_rows() {
+        let board = |lines: usize, score: usize| {
+            let positions: Vec<_> = (0..lines)
+                .flat_map(|row| (0..BOARD_DIMENSION).map(move |col| (row, col)))
+                .collect();
+            Board::builder()
+                .placed(*board_with_placed(&positions).placed())
+                .score(score)
+                .build()
+        };
+
+        let state = state_with(vec![board(1, 30), board(2, 30), board(3, 29)], &[]);
+        assert_eq!(state.winners(), [1]);
+        assert_eq!(state.result(), GameResult::Win(1));
+
+        let state = state_with(vec![board(2, 30), board(1, 30), board(2, 30)], &[]);
+        assert_eq!(state.winners(), [0, 2]);
+        assert_eq!(state.result(), GameResult::Draw(vec![0, 2]));
+        assert_eq!(state.get_winner(), 0);
+
+        let state = state_with(vec![board(1, 20), board(0, 25)], &[]);
+        assert_eq!(state.winners(), [1]);
+    }
+
+    #[test]
+    fn holding_an_already_placed_color_is_illegal() {
+        let board = board_with_placed(&[(3, 1)]);
+        let placed = Board::get_tile_type_at_pos(3, 1);
+        let mut held = board;
+        assert_eq!(
+            held.hold_tiles(placed, 2, Row::Wall(3), 1),
+            Err(IllegalMoveReason::RowAlreadyHasColorPlaced)
+        );
+        assert_eq!(held, board);
+
+        let mut state = state_with(vec![board, Board::default()], &[&[], &[placed]]);
+        let choice = Move {
+            bowl: 1,
+            tile_type: placed,
+            row: Row::Wall(3),
+        };
+        assert_eq!(
+            state.make_move(&choice),
+            Err(IllegalMoveError {
+                choice: choice.clone(),
+                reason: IllegalMoveReason::RowAlreadyHasColorPlaced,
+            })
+        );
+    }
+
+    #[test]
+    fn round_reports_include_row_bonuses() {
+        let mut board = board_with_placed(&[(2, 0), (2, 1), (2, 2), (2, 3)]);
+        let missing = Board::get_tile_type_at_pos(2, 4);
+        board.hold_tiles(missing, 3, Row::Wall(2), 0).unwrap();
+        let mut state = state_with(vec![Board::default(), board], &[]);
+        assert!(state.round_over());
+
+        let reports = state.setup_next_round();
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].bonuses.is_empty());
+        assert_eq!(reports[1].bonuses, [BonusEvent::Row(2)]);
+        assert_eq!(reports[1].placed, [(2, 4)]);
+        assert_eq!(reports[1].placement_points, 5);
+        assert_eq!(reports[1].score_change, 5 + 2);
+    }
+
+    #[test]
+    fn builder_checks_bowl_count() {
+        let built = GameState::builder()
+            .boards(vec![Board::default(); 3])
+            .bowls(vec![Bowl::from_tiles(vec![0, 1]); 8])
+            .bag(Bag::new_ordered(vec![2, 3, 4]))
+            .try_build()
+            .unwrap();
+        assert_eq!(built.player_count(), 3);
+        assert_eq!(built.tiles_in_bowls(), 16);
+        built.debug_assert_tile_conservation();
+
+        let result = GameState::builder()
+            .boards(vec![Board::default(); 3])
+            .bowls(vec![Bowl::default(); 6])
+            .try_build();
+        assert_eq!(
+            result.err(),
+            Some(InvalidGameStateError::BowlCount {
+                expected: 8,
+                found: 6
+            })
+        );
+        assert_eq!(
+            GameState::builder().try_build().err(),
+            Some(InvalidGameStateError::NoPlayers)
+        );
+    }
+
+    #[test]
+    fn color_bonus_needs_enough_tiles_left() {
+        let rules = RulesConfig {
+            tiles_per_type: 6,
+            ..RulesConfig::default()
+        };
+        // Every tile of type 0 which isn't placed is left in the discard
+        let with_opponent_placing = |positions: &[(usize, usize)]| {
+            GameState::builder()
+                .boards(vec![Board::default(), board_with_placed(positions)])
+                .bowls(vec![Bowl::default(); rules.bowl_count(2)])
+                .rules(rules)
+                .try_build()
+                .unwrap()
+        };
+
+        let state = with_opponent_placing(&[(0, 0)]);
+        assert_eq!(state.discarded_total(), 6 * TILE_TYPES - 1);
+        assert!(state.color_bonus_reachable(0, 0));
+
+        let state = with_opponent_placing(&[(0, 0), (1, 1)]);
+        assert!(!state.color_bonus_reachable(0, 0));
+        assert!(state.color_bonus_reachable(1, 0));
+        assert!(state.color_bonus_reachable(0, 1));
+    }
+
+    #[test]
+    fn fresh_deals_fill_every_bowl_but_the_centre() {
+        let mut state = GameState::new_seeded(2, 1);
+        state.setup_next_round();
+        let filled = state.bowls().len() - 1;
+        assert_eq!(state.tiles_in_bowls(), filled * crate::rules::BOWL_CAPACITY);
+        assert_eq!(state.tiles_in_centre(), 0);
+    }
+
+    #[test]
+    fn bowls_with_reports_every_bowl_holding_a_color() {
+        let state = state_with(
+            vec![Board::default(), Board::default()],
+            &[&[0], &[0, 0, 1, 1], &[1, 2, 3, 4], &[0, 2, 2, 3]],
+        );
+        assert_eq!(state.bowls_with(0), [(0, 1), (1, 2), (3, 1)]);
+        assert_eq!(state.bowls_with(4), [(2, 1)]);
+        assert!(state.bowls_with(5).is_empty());
+    }
+
+    #[test]
+    fn move_reports_wrap_the_next_player() {
+        let mut state = GameState::new_seeded(3, 1);
+        state.setup_next_round();
+        for expected in [1, 2, 0] {
+            let choice = state.get_valid_moves()[0].clone();
+            let report = state.make_move_detailed(&choice).unwrap();
+            assert_eq!(report.next_player, expected);
+            assert_eq!(report.next_player, *state.active_player());
+            assert!(!report.round_ended);
+        }
+    }
+
+    #[test]
+    fn final_scores_now_match_scoring_by_hand() {
+        let mut board = board_with_placed(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        let tile_type = Board::get_tile_type_at_pos(0, 0);
+        board.hold_tiles(tile_type, 1, Row::Wall(0), 0).unwrap();
+        board.hold_tiles(tile_type, 2, Row::Floor, 0).unwrap();
+        let state = state_with(vec![board, Board::default()], &[&[1]]);
+
+        // The row of five scores 5, completing it adds the row bonus of 2, and two floor tiles lose 2
+        assert_eq!(state.final_scores_now(), [5 + 2 - 2, 0]);
+        assert_eq!(state.scores().collect::<Vec<_>>(), [0, 0]);
+    }
+
+    #[test]
+    fn positions_without_tiles_are_deadlocked() {
+        // Without a tileset, every tile is accounted for once the bowls are empty
+        let rules = RulesConfig {
+            tiles_per_type: 0,
+            ..RulesConfig::default()
+        };
+        let state = GameState::builder()
+            .boards(vec![Board::default(), Board::default()])
+            .bowls(vec![Bowl::default(); rules.bowl_count(2)])
+            .rules(rules)
+            .try_build()
+            .unwrap();
+        assert!(state.round_over());
+        assert!(!state.is_game_over());
+        assert!(state.is_deadlocked());
+
+        let state = state_with(vec![Board::default(), Board::default()], &[&[], &[0]]);
+        assert!(!state.is_deadlocked());
+    }
+
+    #[test]
+    fn centre_leftovers_are_discarded_on_setup() {
+        let bowls = RulesConfig::default().bowl_count(2);
+        let mut all_bowls = vec![Bowl::default(); bowls];
+        all_bowls[CENTRE_BOWL_IDX].fill(vec![0, 1, 1]);
+        let bag: Vec<Tile> = tile_types().cycle().take((bowls - 1) * 4).collect();
+        let mut state = GameState::builder()
+            .boards(vec![Board::default(), Board::default()])
+            .bowls(all_bowls)
+            .bag(Bag::new_ordered(bag))
+            .try_build()
+            .unwrap();
+        let discarded = state.discarded_total();
+
+        state.setup_next_round();
+        assert_eq!(state.tiles_in_centre(), 0);
+        assert_eq!(state.discarded_total(), discarded + 3);
+        assert!(state.bag().items().is_empty());
+    }
+
+    #[test]
+    fn three_player_games_count_three_players() {
+        let state = GameState::new(3);
+        assert_eq!(state.player_count(), 3);
+        assert_eq!(state.player_count(), state.boards().len());
+    }
+
+    #[test]
+    fn illegal_move_errors_carry_the_move() {
+        let mut state = state_with(
+            vec![Board::default(), Board::default()],
+            &[&[], &[0, 0, 1, 1]],
+        );
+        let choice = Move {
+            bowl: 1,
+            tile_type: 2,
+            row: Row::Wall(4),
+        };
+        let error = state.make_move(&choice).unwrap_err();
+        assert_eq!(error.choice, choice);
+        assert_eq!(error.reason, IllegalMoveReason::ColorNotInBowl);
+    }
+
+    #[test]
+    fn only_the_first_centre_take_is_penalized() {
+        let mut state = state_with(
+            vec![Board::default(), Board::default()],
+            &[&[0, 1], &[2, 2, 3, 3]],
+        );
+        assert_eq!(state.centre_penalty_for(CENTRE_BOWL_IDX), 1);
+        assert_eq!(state.centre_penalty_for(1), 0);
+
+        let take = |tile_type| Move {
+            bowl: CENTRE_BOWL_IDX,
+            tile_type,
+            row: Row::Floor,
+        };
+        state.make_move(&take(0)).unwrap();
+        assert_eq!(state.centre_penalty_for(CENTRE_BOWL_IDX), 0);
+        state.make_move(&take(1)).unwrap();
+        assert_eq!(*state.boards()[0].penalties(), 2);
+        assert_eq!(*state.boards()[1].penalties(), 1);
+    }
+
+    #[test]
+    fn incomplete_holds_carry_unless_the_rules_clear_them() {
+        let mut board = Board::default();
+        board.hold_tiles(1, 2, Row::Wall(2), 0).unwrap();
+        for carry_incomplete_holds in [true, false] {
+            let rules = RulesConfig {
+                carry_incomplete_holds,
+                ..RulesConfig::default()
+            };
+            let mut state = GameState::builder()
+                .boards(vec![board, Board::default()])
+                .bowls(vec![Bowl::default(); rules.bowl_count(2)])
+                .rules(rules)
+                .try_build()
+                .unwrap();
+            let discarded = state.discarded_total();
+
+            state.score_round();
+            let held = state.boards()[0].holds()[2].iter().flatten().count();
+            if carry_incomplete_holds {
+                assert_eq!(held, 2);
+                assert_eq!(state.discarded_total(), discarded);
+            } else {
+                assert_eq!(held, 0);
+                assert_eq!(state.discarded_total(), discarded + 2);
+            }
+        }
+    }
+
+    #[test]
+    fn moves_grouped_by_bowl_flatten_to_the_valid_moves() {
+        let mut state = GameState::new_seeded(2, 4);
+        state.setup_next_round();
+        let choice = state.get_valid_moves()[0].clone();
+        state.make_move(&choice).unwrap();
+
+        let grouped = state.legal_moves_by_bowl();
+        assert!(grouped.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(
+            grouped.iter().all(|(bowl, moves)| {
+                !moves.is_empty() && moves.iter().all(|m| m.bowl == *bowl)
+            })
+        );
+        let flat: Vec<_> = grouped.into_iter().flat_map(|(_, moves)| moves).collect();
+        assert_eq!(flat, state.get_valid_moves());
+    }
+
+    #[test]
+    fn cached_moves_are_invalidated_by_moves() {
+        let mut state = GameState::new_seeded(2, 5);
+        state.setup_next_round();
+        state.set_cache_moves(true);
+
+        let cached = state.get_valid_moves();
+        assert!(state.move_cache.get().is_some());
+        assert_eq!(cached, state.valid_moves_for(*state.active_player()));
+        assert_eq!(state.get_valid_moves(), cached);
+
+        state.make_move(&cached[0]).unwrap();
+        assert!(state.move_cache.get().is_none());
+        assert_eq!(
+            state.get_valid_moves(),
+            state.valid_moves_for(*state.active_player())
+        );
+        // Legality is judged by the new position, not the moves cached before it
+        assert!(
+            cached
+                .iter()
+                .all(|choice| state.is_legal(choice) == state.check_move(choice).is_ok())
+        );
+    }
+
+    #[test]
+    fn ordered_bags_fill_bowls_in_order() {
+        let bowls = RulesConfig::default().bowl_count(2);
+        let tiles: Vec<Tile> = tile_types().cycle().take((bowls - 1) * 4 + 3).collect();
+        let mut state = GameState::builder()
+            .boards(vec![Board::default(), Board::default()])
+            .bowls(vec![Bowl::default(); bowls])
+            .bag(Bag::new_ordered(tiles.clone()))
+            .try_build()
+            .unwrap();
+        state.setup_next_round();
+
+        // Each bowl is filled with the next tiles in order, which bowls keep sorted
+        for (bowl, expected) in state.bowls()[1..].iter().zip(tiles.chunks(4)) {
+            let mut expected = expected.to_vec();
+            expected.sort();
+            assert_eq!(*bowl.tiles(), expected);
+        }
+        assert_eq!(state.bag().items().len(), 3);
+    }
+
+    #[test]
+    fn scoring_then_dealing_matches_setting_up() {
+        let mut state = GameState::new_seeded(2, 12);
+        state.setup_next_round();
+        let mut rng = StdRng::seed_from_u64(12);
+        while !state.round_over() {
+            let choice = state.get_valid_moves().choose(&mut rng).unwrap().clone();
+            state.make_move(&choice).unwrap();
+        }
+
+        let mut combined = state.clone();
+        let combined_reports = combined.setup_next_round();
+        let mut stepped = state;
+        let stepped_reports = stepped.score_round();
+        stepped.deal_round();
+        assert_eq!(stepped, combined);
+        assert_eq!(stepped_reports, combined_reports);
+    }
+
+    #[test]
+    fn out_of_range_active_players_have_no_moves() {
+        let builder = || {
+            GameState::builder()
+                .boards(vec![Board::default(), Board::default()])
+                .bowls(vec![
+                    Bowl::from_tiles(vec![0, 1]);
+                    RulesConfig::default().bowl_count(2)
+                ])
+        };
+        assert!(builder().active_player(2).validate().is_err());
+        let state = builder().active_player(2).build();
+        assert_eq!(state.try_get_valid_moves(), None);
+
+        let state = builder().active_player(1).build();
+        assert_eq!(state.try_get_valid_moves(), Some(state.get_valid_moves()));
+    }
+
+    #[test]
+    fn summary_lines_label_each_player() {
+        let board = Board::builder().score(23).penalties(2).build();
+        let state = state_with(vec![Board::default(), board], &[]);
+        assert_eq!(
+            state.summary_lines(),
+            "P0 score=0 pen=0 lines=0\nP1 score=23 pen=2 lines=0"
+        );
+    }
+
+    #[test]
+    fn previewing_a_take_leaves_the_state_alone() {
+        let state = state_with(
+            vec![Board::default(), Board::default()],
+            &[&[2], &[0, 0, 1, 1]],
+        );
+        let before = state.clone();
+        assert_eq!(state.preview_take(1, 0), (2, 2));
+        assert_eq!(state.preview_take(1, 1), (2, 2));
+        assert_eq!(state.preview_take(CENTRE_BOWL_IDX, 2), (1, 0));
+        assert_eq!(state.preview_take(1, 3), (0, 0));
+        assert_eq!(state, before);
+
+        let mut taken = state.clone();
+        let report = taken
+            .make_move_detailed(&Move {
+                bowl: 1,
+                tile_type: 0,
+                row: Row::Wall(1),
+            })
+            .unwrap();
+        assert_eq!((report.to_row + report.to_floor, report.to_centre), (2, 2));
+    }
+
+    #[test]
+    fn played_rounds_conserve_tiles() {
+        let mut state = GameState::new_seeded(3, 13);
+        state.setup_next_round();
+        state.debug_assert_tile_conservation();
+        let mut rng = StdRng::seed_from_u64(13);
+        while !state.round_over() {
+            let choice = state.get_valid_moves().choose(&mut rng).unwrap().clone();
+            state.make_move(&choice).unwrap();
+            state.debug_assert_tile_conservation();
+        }
+        state.setup_next_round();
+        state.debug_assert_tile_conservation();
+        assert_eq!(*state.round(), 2);
+    }
+
+    #[test]
+    fn per_bowl_counts_sum_to_the_valid_moves() {
+        let mut state = GameState::new_seeded(2, 14);
+        state.setup_next_round();
+        for _ in 0..3 {
+            let counts = state.move_counts_per_bowl();
+            assert_eq!(counts.len(), state.bowls().len());
+            assert_eq!(counts.iter().sum::<usize>(), state.get_valid_moves().len());
+            for (bowl, &count) in counts.iter().enumerate() {
+                let from_bowl = state
+                    .get_valid_moves()
+                    .iter()
+                    .filter(|m| m.bowl == bowl)
+                    .count();
+                assert_eq!(count, from_bowl);
+            }
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+    }
+
+    #[test]
+    fn untaken_centres_keep_the_round_starter() {
+        let mut all_bowls = vec![Bowl::default(); RulesConfig::default().bowl_count(2)];
+        all_bowls[1].fill(vec![0; 4]);
+        all_bowls[2].fill(vec![1; 4]);
+        let mut state = GameState::builder()
+            .boards(vec![Board::default(), Board::default()])
+            .bowls(all_bowls)
+            .active_player(1)
+            .round_starter(1)
+            .round(1)
+            .try_build()
+            .unwrap();
+
+        // Every take empties its bowl, so nothing is ever left in the centre
+        for bowl in [1, 2] {
+            let tile_type = state.bowls()[bowl].tiles()[0];
+            state
+                .make_move(&Move {
+                    bowl,
+                    tile_type,
+                    row: Row::Floor,
+                })
+                .unwrap();
+        }
+        assert!(state.round_over());
+        assert_eq!(*state.first_token_owner(), None);
+
+        state.score_round();
+        assert_eq!(*state.active_player(), 1);
+        assert_eq!(*state.round_starter(), 1);
+    }
+
+    #[test]
+    fn simulated_rounds_drain_the_bowls() {
+        let mut state = GameState::new_seeded(2, 15);
+        state.setup_next_round();
+        let reports = state.simulate_round(&greedy);
+        assert_eq!(state.tiles_in_bowls(), 0);
+        assert_eq!(reports.len(), 2);
+        assert!(reports.iter().any(|report| !report.placed.is_empty()));
+        assert_eq!(*state.round(), 1);
+
+        state.deal_round();
+        assert!(!state.round_over());
+    }
+
+    #[test]
+    fn win_estimates_favour_the_leader() {
+        let leader = Board::builder().score(40).build();
+        let trailer = Board::builder().score(5).build();
+        let state = state_with(vec![trailer, leader], &[&[], &[0, 0, 1, 1]]);
+        let estimate = state.win_estimate();
+        assert_eq!(estimate.len(), 2);
+        assert!(estimate[1] > estimate[0]);
+        assert!((estimate.iter().sum::<f32>() - 1.).abs() < 1e-4);
+
+        let even = state_with(vec![Board::default(), Board::default()], &[&[], &[0]]);
+        let estimate = even.win_estimate();
+        assert!((estimate[0] - estimate[1]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn rotating_starters_advance_each_round() {
+        let rules = RulesConfig {
+            first_player_rule: FirstPlayerRule::Rotate,
+            ..RulesConfig::default()
+        };
+        let mut state = GameState::with_rules_seeded(3, rules, 17);
+        state.setup_next_round();
+        let first = *state.round_starter();
+        for round in 1..4 {
+            while !state.round_over() {
+                let choice = state.get_valid_moves()[0].clone();
+                state.make_move(&choice).unwrap();
+            }
+            state.setup_next_round();
+            assert_eq!(*state.round_starter(), (first + round) % 3);
+            assert_eq!(*state.active_player(), *state.round_starter());
+        }
+    }
+
+    #[test]
+    fn active_board_follows_the_active_player() {
+        let mut state = GameState::new_seeded(3, 18);
+        state.setup_next_round();
+        for _ in 0..4 {
+            let active = *state.active_player();
+            assert!(std::ptr::eq(state.active_board(), &state.boards()[active]));
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+        let active = *state.active_player();
+        let penalties = *state.boards()[active].penalties();
+        state
+            .active_board_mut()
+            .hold_tiles(0, 1, Row::Floor, 0)
+            .unwrap();
+        assert_eq!(*state.boards()[active].penalties(), penalties + 1);
+    }
+
+    #[test]
+    fn available_tile_types_are_a_sorted_union() {
+        let state = state_with(
+            vec![Board::default(), Board::default()],
+            &[&[3], &[3, 3, 1, 1], &[1, 1, 1, 1]],
+        );
+        assert_eq!(state.available_tile_types(), [1, 3]);
+        let empty = state_with(vec![Board::default(), Board::default()], &[]);
+        assert!(empty.available_tile_types().is_empty());
+    }
+
+    #[test]
+    fn swapping_twice_restores_players() {
+        let mut state = GameState::new_seeded(3, 4);
+        state.setup_next_round();
+        state.random_playout(&mut StdRng::seed_from_u64(4));
+
+        let swapped = state.with_swapped_players();
+        assert_eq!(swapped.boards()[0], state.boards()[2]);
+        assert_eq!(*swapped.active_player(), 2 - state.active_player());
+        assert!(*swapped.players_swapped());
+
+        let restored = swapped.with_swapped_players();
+        assert!(restored.position_eq(&state));
+        assert_eq!(restored.active_player(), state.active_player());
+        assert_eq!(restored.round_starter(), state.round_starter());
+        assert_eq!(restored.bag().items(), state.bag().items());
+        assert!(!*restored.players_swapped());
+    }
+
+    #[test]
+    fn swapped_games_replay_from_seed() {
+        let mut state = GameState::new_seeded(2, 8);
+        state.set_record_history(true);
+        state.setup_next_round();
+        let mut state = state.with_swapped_players();
+        state.random_playout(&mut StdRng::seed_from_u64(8));
+
+        let replay = state.replay_from_start().unwrap();
+        assert!(replay.position_eq(&state));
+        assert!(*replay.players_swapped());
+    }
+
+    #[test]
+    fn unpenalized_centre_takes_still_claim_the_token() {
+        let rules = RulesConfig {
+            centre_penalty: 0,
+            ..RulesConfig::default()
+        };
+        let mut all_bowls = vec![Bowl::default(); rules.bowl_count(2)];
+        all_bowls[CENTRE_BOWL_IDX].fill(vec![0, 1]);
+        let mut state = GameState::builder()
+            .boards(vec![Board::default(), Board::default()])
+            .bowls(all_bowls)
+            .rules(rules)
+            .try_build()
+            .unwrap();
+        assert_eq!(state.centre_penalty_for(CENTRE_BOWL_IDX), 0);
+
+        state
+            .make_move(&Move {
+                bowl: CENTRE_BOWL_IDX,
+                tile_type: 0,
+                row: Row::Wall(0),
+            })
+            .unwrap();
+        assert_eq!(*state.first_token_owner(), Some(0));
+        assert_eq!(*state.boards()[0].penalties(), 0);
+    }
+
+    #[test]
+    fn first_token_returns_each_round() {
+        let mut state = GameState::new_seeded(2, 19);
+        state.setup_next_round();
+        assert!(state.first_token_available());
+
+        // Taking from a bowl leaves the token, but taking from the centre claims it
+        let choice = state.get_valid_moves().last().unwrap().clone();
+        state.make_move(&choice).unwrap();
+        assert!(state.first_token_available());
+        assert!(state.tiles_in_centre() > 0);
+        let take = state.get_valid_moves()[0].clone();
+        assert_eq!(take.bowl, CENTRE_BOWL_IDX);
+        state.make_move(&take).unwrap();
+        assert!(!state.first_token_available());
+
+        while !state.round_over() {
+            let choice = state.get_valid_moves()[0].clone();
+            state.make_move(&choice).unwrap();
+        }
+        state.setup_next_round();
+        assert!(state.first_token_available());
+    }
+
+    #[test]
+    fn position_eq_ignores_active_player() {
+        let mut state = GameState::new_seeded(2, 10);
+        state.setup_next_round();
+        assert_eq!(state, state.clone());
+
+        let mut other = GameState::builder()
+            .active_player(1)
+            .boards(state.boards().clone())
+            .bowls(state.bowls().clone())
+            .bag(state.bag().clone())
+            .build();
+        assert!(other.position_eq(&state));
+        assert_ne!(other, state);
+
+        other = state.clone();
+        let choice = other.get_valid_moves()[0].clone();
+        other.make_move(&choice).unwrap();
+        assert!(!other.position_eq(&state));
+    }
+
+    #[test]
+    fn round_reports_give_each_net_score_change() {
+        let mut scorer = board_with_placed(&[(0, 1), (0, 2)]);
+        scorer
+            .hold_tiles(Board::get_tile_type_at_pos(0, 0), 1, Row::Wall(0), 0)
+            .unwrap();
+        let mut floored = Board::builder().score(10).build();
+        floored.hold_tiles(1, 4, Row::Floor, 0).unwrap();
+        let mut broke = Board::builder().score(3).build();
+        broke.hold_tiles(2, 4, Row::Floor, 0).unwrap();
+        let mut state = state_with(vec![scorer, floored, broke], &[]);
+
+        let reports = state.score_round();
+        let changes: Vec<_> = reports.iter().map(|r| r.score_change).collect();
+        // Scores can't drop below zero, so the last player only loses the points they had
+        assert_eq!(changes, [3, -6, -3]);
+        assert_eq!(state.scores().collect::<Vec<_>>(), [3, 4, 0]);
+    }
+
+    #[test]
+    fn taking_an_opponents_last_needed_tiles_is_denial() {
+        let mut opponent = Board::default();
+        opponent.hold_tiles(3, 2, Row::Wall(2), 0).unwrap();
+        let state = state_with(
+            vec![Board::default(), opponent],
+            &[&[], &[3, 0, 0, 0], &[1, 1, 2, 2]],
+        );
+        let take = |bowl, tile_type| Move {
+            bowl,
+            tile_type,
+            row: Row::Floor,
+        };
+        assert!(state.is_denial_move(&take(1, 3)));
+        assert!(!state.is_denial_move(&take(1, 0)));
+        assert!(!state.is_denial_move(&take(2, 1)));
+        assert!(!state.is_denial_move(&take(2, 3)));
+    }
+
+    #[test]
+    fn randomized_starts_follow_the_seed() {
+        let rules = RulesConfig {
+            randomize_start: true,
+            ..RulesConfig::default()
+        };
+        let starter = |seed| {
+            let mut state = GameState::with_rules_seeded(4, rules, seed);
+            state.setup_next_round();
+            *state.active_player()
+        };
+        let starters: Vec<_> = (0..16).map(starter).collect();
+        assert!(starters.iter().any(|&s| s != starters[0]));
+        assert_eq!((0..16).map(starter).collect::<Vec<_>>(), starters);
+
+        // Without randomization, the first player always starts
+        let mut state = GameState::new_seeded(4, 3);
+        state.setup_next_round();
+        assert_eq!(*state.active_player(), 0);
+    }
+
+    #[test]
+    fn with_move_leaves_the_original_untouched() {
+        let mut state = GameState::new_seeded(2, 20);
+        state.setup_next_round();
+        let before = state.clone();
+        let choice = state.get_valid_moves()[3].clone();
+
+        let moved = state.with_move(&choice).unwrap();
+        let mut expected = state.clone();
+        expected.make_move(&choice).unwrap();
+        assert_eq!(moved, expected);
+        assert_eq!(state, before);
+        assert_ne!(moved, state);
+
+        let illegal = Move { bowl: 99, ..choice };
+        assert!(state.with_move(&illegal).is_err());
+    }
+
+    #[test]
+    fn identical_bowls_are_listed_by_index() {
+        let state = state_with(
+            vec![Board::default(), Board::default()],
+            &[&[], &[2, 2, 4, 4], &[0, 1, 1, 3], &[2, 2, 4, 4]],
+        );
+        let moves = state.get_valid_moves();
+        let first_of = |bowl| moves.iter().position(|m| m.bowl == bowl).unwrap();
+        let last_of = |bowl| moves.iter().rposition(|m| m.bowl == bowl).unwrap();
+        assert!(last_of(1) < first_of(3));
+
+        // Both bowls give the same moves, apart from the bowl
+        let from = |bowl| -> Vec<_> {
+            moves
+                .iter()
+                .filter(|m| m.bowl == bowl)
+                .map(|m| (m.tile_type, m.row))
+                .collect()
+        };
+        assert_eq!(from(1), from(3));
+        let grouped: Vec<_> = state
+            .legal_moves_by_bowl()
+            .iter()
+            .map(|(b, _)| *b)
+            .collect();
+        assert_eq!(grouped, [1, 2, 3]);
+    }
+
+    #[test]
+    fn depleted_pools_cannot_deal() {
+        let rules = RulesConfig {
+            tiles_per_type: 1,
+            ..RulesConfig::default()
+        };
+        let with_boards = |boards: Vec<Board>| {
+            GameState::builder()
+                .boards(boards)
+                .bowls(vec![Bowl::default(); rules.bowl_count(2)])
+                .rules(rules)
+                .try_build()
+                .unwrap()
+        };
+
+        // Every tile type has a single tile, all of which are in the discard
+        let state = with_boards(vec![Board::default(), Board::default()]);
+        assert_eq!(state.discarded_total(), TILE_TYPES);
+        assert!(state.can_deal_next_round());
+
+        // With two of them on the walls, too few are left to fill a bowl
+        let placed = board_with_placed(&[(0, 0), (1, 0)]);
+        let state = with_boards(vec![placed, Board::default()]);
+        assert_eq!(state.discarded_total(), TILE_TYPES - 2);
+        assert!(!state.can_deal_next_round());
+    }
+
+    #[test]
+    fn takeable_colors_match_the_bowl() {
+        // The active player can't place blue anywhere on their wall, but can always drop it
+        let board = board_with_placed(&[(0, 0), (1, 1), (2, 2), (3, 3), (4, 4)]);
+        let state = state_with(vec![board, Board::default()], &[&[], &[4, 0, 0, 2]]);
+        assert_eq!(state.takeable_colors(1), [0, 2, 4]);
+        assert!(state.takeable_colors(CENTRE_BOWL_IDX).is_empty());
+        assert!(state.takeable_colors(99).is_empty());
+    }
+
+    #[test]
+    fn row_completing_takes_are_valued_higher() {
+        let board = board_with_placed(&[(0, 1), (0, 2), (0, 3), (0, 4)]);
+        let completing = Board::get_tile_type_at_pos(0, 0);
+        let other = (completing + 1) % TILE_TYPES;
+        let state = state_with(
+            vec![board, Board::default()],
+            &[
+                &[],
+                &[completing, other, other, other],
+                &[other, other, other, other],
+            ],
+        );
+
+        let better = state.expected_take_value(1, completing, 50);
+        let worse = state.expected_take_value(2, other, 50);
+        assert!(better > worse, "{} <= {}", better, worse);
+        assert_eq!(state.expected_take_value(1, completing, 50), better);
+        assert_eq!(
+            state.expected_take_value(2, completing, 50),
+            f32::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn restocking_mid_fill_neither_duplicates_nor_loses_tiles() {
+        let rules = RulesConfig {
+            tiles_per_type: 5,
+            ..RulesConfig::default()
+        };
+        let mut state = GameState::with_rules_seeded(2, rules, 24);
+        state.setup_next_round();
+        let fill = (state.bowls().len() - 1) * rules.bowl_capacity;
+        let tiles = |state: &GameState| {
+            let mut tiles: Vec<Tile> = state
+                .bag
+                .items()
+                .iter()
+                .chain(state.bowls.iter().flat_map(|b| b.tiles()))
+                .chain(state.discard.iter())
+                .copied()
+                .chain(state.boards.iter().flat_map(|b| b.get_active_tiles()))
+                .collect();
+            tiles.sort();
+            tiles
+        };
+
+        let mut rng = StdRng::seed_from_u64(24);
+        let mut restocked_mid_fill = false;
+        while *state.round() < 5 && !state.is_game_over() {
+            while !state.round_over() {
+                let choice = state.get_valid_moves().choose(&mut rng).unwrap().clone();
+                state.make_move(&choice).unwrap();
+            }
+            let in_bag = state.bag().items().len();
+            restocked_mid_fill |= in_bag > 0 && in_bag < fill;
+            state.setup_next_round();
+            assert_eq!(tiles(&state), rules.tileset());
         }
+        assert!(restocked_mid_fill);
     }
 }
@@ -0,0 +1,115 @@
+use crate::{Board, GameState, Tile, board::BOARD_DIMENSION, tile_source::CENTRE_BOWL_IDX};
+
+impl GameState {
+    /// Returns a JSON document describing the public state of this game, without requiring any
+    /// serialization dependencies. The bag is not included.
+    ///
+    /// The document is a single object with the following keys, always in this order:
+    /// * `player_count`: the number of players.
+    /// * `active_player`: the index of the player to move.
+    /// * `first_token_owner`: the index of the player holding the first player token, or `null`
+    ///   if it is still in the centre.
+    /// * `round`: the current round number.
+    /// * `move_count`: the number of moves played.
+    /// * `scores`: the score of each player, in player order.
+    /// * `boards`: an object for each player, with keys `score`, `penalties`, `holds`, and
+    ///   `placed`. `holds` and `placed` are square arrays of rows, with each position holding
+    ///   its tile type, or `null` if empty.
+    /// * `centre`: the tile types in the centre.
+    /// * `bowls`: an array of the tile types in each bowl, excluding the centre.
+    pub fn to_json(&self) -> String {
+        let scores: Vec<_> = self
+            .boards()
+            .iter()
+            .map(|b| b.score().to_string())
+            .collect();
+        let boards: Vec<_> = self.boards().iter().map(board_json).collect();
+        let bowls: Vec<_> = self
+            .bowls()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != CENTRE_BOWL_IDX)
+            .map(|(_, b)| tiles_json(b.tiles()))
+            .collect();
+        format!(
+            "{{\"player_count\":{},\"active_player\":{},\"first_token_owner\":{},\"round\":{},\"move_count\":{},\
+             \"scores\":[{}],\"boards\":[{}],\"centre\":{},\"bowls\":[{}]}}",
+            self.player_count(),
+            self.active_player(),
+            self.first_token_owner()
+                .map_or("null".to_string(), |p| p.to_string()),
+            self.round(),
+            self.move_count(),
+            scores.join(","),
+            boards.join(","),
+            tiles_json(self.centre().tiles()),
+            bowls.join(",")
+        )
+    }
+}
+
+/// Returns the JSON object for a single board.
+fn board_json(board: &Board) -> String {
+    let grid = |rows: &[[Option<Tile>; BOARD_DIMENSION]]| {
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                let cells: Vec<_> = row
+                    .iter()
+                    .map(|t| t.map_or("null".to_string(), |t| t.to_string()))
+                    .collect();
+                format!("[{}]", cells.join(","))
+            })
+            .collect();
+        format!("[{}]", rows.join(","))
+    };
+    format!(
+        "{{\"score\":{},\"penalties\":{},\"holds\":{},\"placed\":{}}}",
+        board.score(),
+        board.penalties(),
+        grid(board.holds()),
+        grid(board.placed())
+    )
+}
+
+/// Returns a JSON array of the given tiles.
+fn tiles_json(tiles: &[Tile]) -> String {
+    let tiles: Vec<_> = tiles.iter().map(|t| t.to_string()).collect();
+    format!("[{}]", tiles.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_has_every_key_and_square_grids() {
+        let mut state = GameState::new_seeded(2, 28);
+        state.setup_next_round();
+        let json = state.to_json();
+
+        let keys = [
+            "player_count",
+            "active_player",
+            "first_token_owner",
+            "round",
+            "move_count",
+            "scores",
+            "boards",
+            "centre",
+            "bowls",
+        ];
+        let mut last = 0;
+        for key in keys {
+            let at = json.find(&format!("\"{}\":", key)).expect(key);
+            assert!(at > last || last == 0, "{} out of order", key);
+            last = at;
+        }
+        assert!(json.starts_with("{\"player_count\":2,"));
+
+        let row = format!("[{}]", ["null"; BOARD_DIMENSION].join(","));
+        let grid = format!("[{}]", vec![row; BOARD_DIMENSION].join(","));
+        assert!(json.contains(&format!("\"placed\":{}", grid)));
+        assert_eq!(json.matches("\"placed\":").count(), 2);
+    }
+}
@@ -6,8 +6,9 @@ pub type Tile = usize;
 
 /// Macro to help make getters.
 macro_rules! getters {
-    ($($field:ident : $ty:ty), *$(,)?) => {
+    ($($(#[$meta:meta])* $field:ident : $ty:ty), *$(,)?) => {
         $(
+            $(#[$meta])*
             pub fn $field(&self) -> &$ty {
                 &self.$field
             }
@@ -15,17 +16,36 @@ macro_rules! getters {
     }
 }
 
+pub mod bench;
 pub mod board;
+pub mod color;
+pub mod eval;
+pub mod fair_view;
+pub mod fen;
 pub mod game_move;
+pub mod game_result;
 pub mod gamestate;
+pub mod rules;
+pub mod search;
+pub mod stats;
+pub mod tile_source;
 
 mod bag;
 mod bowl;
+mod json;
 mod row;
+mod transcript;
 
 pub use bag::Bag;
 pub use board::Board;
 pub use bowl::Bowl;
+pub use color::Color;
+pub use eval::EvalWeights;
+pub use fair_view::FairView;
 pub use game_move::Move;
+pub use game_result::GameResult;
 pub use gamestate::GameState;
 pub use row::Row;
+pub use rules::RulesConfig;
+pub use stats::PlayoutStats;
+pub use tile_source::TileSource;
@@ -8,3 +8,40 @@ pub enum Row {
     /// Tiles may only be placed on the wall in valid rows. The parameter `usize` represents the index from top to bottom.
     Wall(usize),
 }
+
+impl Row {
+    /// Returns the row with the given number in the move protocol, where `0` is the floor and
+    /// wall rows are numbered from `1` at the top.
+    pub fn from_protocol(row: usize) -> Row {
+        match row {
+            0 => Row::Floor,
+            r => Row::Wall(r - 1),
+        }
+    }
+
+    /// Returns the number of this row in the move protocol, where `0` is the floor and wall rows
+    /// are numbered from `1` at the top.
+    pub fn to_protocol(&self) -> usize {
+        match self {
+            Row::Floor => 0,
+            Row::Wall(i) => i + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BOARD_DIMENSION;
+
+    #[test]
+    fn protocol_numbers_round_trip() {
+        assert_eq!(Row::from_protocol(0), Row::Floor);
+        assert_eq!(Row::Floor.to_protocol(), 0);
+        for i in 0..BOARD_DIMENSION {
+            let row = Row::Wall(i);
+            assert_eq!(row.to_protocol(), i + 1);
+            assert_eq!(Row::from_protocol(row.to_protocol()), row);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{Tile, board::tile_types};
+
+/// The number of tiles of each type to be added to the bag at the beginning of a standard game,
+/// and to be used for reference during round setup.
+pub const TILES_PER_TYPE: usize = 20;
+
+/// The number of tiles that each bowl is restocked to contain during the round setup of a
+/// standard game.
+pub const BOWL_CAPACITY: usize = 4;
+
+/// The number of penalty tiles incurred by taking the first player token in a standard game.
+pub const CENTRE_PENALTY: usize = 1;
+
+/// The number of completed horizontal lines which ends a standard game.
+pub const END_ON_LINES: usize = 1;
+
+/// Bowl formula is given by 2n + 1, with an additional bowl for the centre space.
+pub fn standard_bowl_count(players: usize) -> usize {
+    players * 2 + 2
+}
+
+/// Decides which player starts each round after the first.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FirstPlayerRule {
+    /// The player who took the first player token from the centre starts the next round, as in
+    /// the standard game.
+    #[default]
+    CentreToken,
+    /// The starting player passes to the next player in turn order each round. The first player
+    /// token is still taken from the centre, along with its penalty, but doesn't decide who starts.
+    Rotate,
+}
+
+/// The rules a game is played with. The default rules match the standard game.
+/// # Properties
+/// * `tiles_per_type`: the number of tiles of each type in play.
+/// * `bowl_capacity`: the number of tiles each bowl is restocked to contain during round setup.
+/// * `bowl_formula`: gives the number of bowls, including the centre, for a number of players.
+/// * `end_on_lines`: the number of horizontal lines any one player must complete to end the game.
+/// * `carry_incomplete_holds`: whether holds which were not completed are kept into the next round,
+///   as in the standard game. Otherwise, their tiles are discarded at the end of each round.
+/// * `first_player_rule`: decides which player starts each round after the first.
+/// * `centre_penalty`: the number of penalty tiles incurred by taking the first player token,
+///   which are counted on the floor like any other penalty tiles.
+/// * `randomize_start`: whether the player who starts the first round is chosen at random, rather
+///   than always being the first player. In seeded games, the choice is determined by the seed.
+#[derive(Debug, Clone, Copy)]
+pub struct RulesConfig {
+    pub tiles_per_type: usize,
+    pub bowl_capacity: usize,
+    pub bowl_formula: fn(usize) -> usize,
+    pub end_on_lines: usize,
+    pub carry_incomplete_holds: bool,
+    pub first_player_rule: FirstPlayerRule,
+    pub centre_penalty: usize,
+    pub randomize_start: bool,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        RulesConfig {
+            tiles_per_type: TILES_PER_TYPE,
+            bowl_capacity: BOWL_CAPACITY,
+            bowl_formula: standard_bowl_count,
+            end_on_lines: END_ON_LINES,
+            carry_incomplete_holds: true,
+            first_player_rule: FirstPlayerRule::CentreToken,
+            centre_penalty: CENTRE_PENALTY,
+            randomize_start: false,
+        }
+    }
+}
+
+impl RulesConfig {
+    /// Returns the number of bowls, including the centre, used for the given number of players.
+    pub fn bowl_count(&self, players: usize) -> usize {
+        (self.bowl_formula)(players)
+    }
+
+    /// Returns an iterator over the full set of tiles used in a game with these rules.
+    /// There is one tile type per wall column, so there are [`TILE_TYPES`](crate::board::TILE_TYPES) tile types.
+    pub fn tiles(&self) -> impl Iterator<Item = Tile> + use<> {
+        let tiles_per_type = self.tiles_per_type;
+        // There should always be the same number of tile types as board width
+        tile_types().flat_map(move |t| std::iter::repeat_n(t, tiles_per_type))
+    }
+
+    /// Generates the full set of tiles used in a game with these rules.
+    pub fn tileset(&self) -> Vec<Tile> {
+        self.tiles().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{GameState, board::BOARD_DIMENSION};
+
+    #[test]
+    fn default_tileset_is_split_evenly() {
+        let tileset = GameState::default_tileset();
+        assert_eq!(tileset.len(), BOARD_DIMENSION * TILES_PER_TYPE);
+        for tile_type in tile_types() {
+            assert_eq!(
+                tileset.iter().filter(|&&t| t == tile_type).count(),
+                TILES_PER_TYPE
+            );
+        }
+        assert!(tileset.is_sorted());
+        assert_eq!(RulesConfig::default().tileset(), tileset);
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::{Duration, Instant};
+
+use crate::{EvalWeights, GameState, Move};
+
+/// Evaluates the given gamestate from the perspective of the given player, where higher values
+/// are better for that player.
+pub type Evaluator = dyn Fn(&GameState, usize) -> f32;
+
+/// A simple evaluator which compares the given player's board evaluation against the best of
+/// their opponents', using the default [`EvalWeights`].
+pub fn evaluate_relative(state: &GameState, player: usize) -> f32 {
+    let weights = EvalWeights::default();
+    let mut own = 0.;
+    let mut best_opponent = None;
+    for (i, board) in state.boards().iter().enumerate() {
+        let eval = board.evaluate(&weights);
+        if i == player {
+            own = eval;
+        } else if best_opponent.is_none_or(|best| eval > best) {
+            best_opponent = Some(eval);
+        }
+    }
+    own - best_opponent.unwrap_or_default()
+}
+
+/// Searches the given gamestate to the given depth with alpha-beta pruning, returning its value
+/// from the perspective of the given player.
+///
+/// Every opponent is assumed to play against the given player. The search does not continue past
+/// the end of the current round, since the next round's deal is random.
+pub fn alpha_beta(
+    state: &GameState,
+    depth: usize,
+    alpha: f32,
+    beta: f32,
+    player: usize,
+    eval: &Evaluator,
+) -> f32 {
+    search(state, depth, alpha, beta, player, eval, None).expect("Search without deadline")
+}
+
+/// Searches with increasing depth until the given time budget is spent, returning the best move
+/// found by the deepest completed search.
+///
+/// The first depth is always searched to completion, so a legal move is returned even if the
+/// budget is too small for any deeper search. Deepening also stops once the end of the current
+/// round is within reach.
+///
+/// # Panics
+/// Panics if the active player has no valid moves.
+pub fn iterative_deepen(state: &GameState, time_budget: Duration, eval: &Evaluator) -> Move {
+    let deadline = Instant::now() + time_budget;
+    let player = *state.active_player();
+    let mut moves = state.get_valid_moves();
+    assert!(!moves.is_empty(), "No valid moves to search");
+
+    // Every move takes at least one tile, so the round can't last longer than this
+    let max_depth = state
+        .bowls()
+        .iter()
+        .map(|b| b.tiles().len())
+        .sum::<usize>()
+        .max(1);
+
+    let mut best = moves[0].clone();
+    for depth in 1..=max_depth {
+        let limit = if depth == 1 { None } else { Some(deadline) };
+        match search_root(state, &moves, depth, player, eval, limit) {
+            Some(choice) => {
+                // Search the best move first next iteration for better pruning
+                let idx = moves.iter().position(|m| *m == choice).unwrap();
+                moves[..=idx].rotate_right(1);
+                best = choice;
+            }
+            None => break,
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+    }
+    best
+}
+
+/// Searches each of the given moves from the root, returning the best, or `None` if the deadline
+/// passed before the search could complete.
+fn search_root(
+    state: &GameState,
+    moves: &[Move],
+    depth: usize,
+    player: usize,
+    eval: &Evaluator,
+    deadline: Option<Instant>,
+) -> Option<Move> {
+    let mut alpha = f32::NEG_INFINITY;
+    let mut best = None;
+    for choice in moves {
+        let mut next = state.clone();
+        next.make_move(choice).expect("Generated move was illegal");
+        let value = search(
+            &next,
+            depth - 1,
+            alpha,
+            f32::INFINITY,
+            player,
+            eval,
+            deadline,
+        )?;
+        if best.is_none() || value > alpha {
+            alpha = value;
+            best = Some(choice.clone());
+        }
+    }
+    best
+}
+
+/// Alpha-beta search which gives up and returns `None` once the optional deadline has passed.
+fn search(
+    state: &GameState,
+    depth: usize,
+    mut alpha: f32,
+    mut beta: f32,
+    player: usize,
+    eval: &Evaluator,
+    deadline: Option<Instant>,
+) -> Option<f32> {
+    if deadline.is_some_and(|d| Instant::now() >= d) {
+        return None;
+    }
+    if depth == 0 || state.round_over() {
+        return Some(eval(state, player));
+    }
+
+    let maximizing = *state.active_player() == player;
+    let mut best = if maximizing {
+        f32::NEG_INFINITY
+    } else {
+        f32::INFINITY
+    };
+    for choice in state.get_valid_moves() {
+        let mut next = state.clone();
+        next.make_move(&choice).expect("Generated move was illegal");
+        let value = search(&next, depth - 1, alpha, beta, player, eval, deadline)?;
+        if maximizing {
+            best = best.max(value);
+            alpha = alpha.max(best);
+        } else {
+            best = best.min(value);
+            beta = beta.min(best);
+        }
+        if alpha >= beta {
+            break;
+        }
+    }
+    Some(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiny_budgets_still_return_legal_moves() {
+        let mut state = GameState::new_seeded(2, 20);
+        state.setup_next_round();
+
+        let budget = Duration::from_millis(20);
+        let start = Instant::now();
+        let choice = iterative_deepen(&state, budget, &evaluate_relative);
+        assert!(state.is_legal(&choice));
+        // A single ply is always searched, so allow it some time past the budget
+        assert!(start.elapsed() < budget * 10);
+
+        let choice = iterative_deepen(&state, Duration::ZERO, &evaluate_relative);
+        assert!(state.is_legal(&choice));
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use crate::game_result::GameResult;
+
+/// Collects aggregate statistics over many completed games, such as self-play playouts.
+/// Collectors from parallel workers can be combined with [`PlayoutStats::merge`].
+#[derive(Debug, Clone, Default)]
+pub struct PlayoutStats {
+    games: usize,
+    total_moves: usize,
+    wins: Vec<usize>,
+    draws: usize,
+    rounds: BTreeMap<usize, usize>,
+}
+
+impl PlayoutStats {
+    /// Creates a new, empty `PlayoutStats`.
+    pub fn new() -> Self {
+        PlayoutStats::default()
+    }
+
+    getters! {
+        games: usize,
+        draws: usize,
+    }
+
+    /// Records a completed game with the given result, lasting the given number of moves and rounds.
+    pub fn record(&mut self, result: GameResult, moves: usize, rounds: usize) {
+        self.games += 1;
+        self.total_moves += moves;
+        *self.rounds.entry(rounds).or_default() += 1;
+        match result {
+            GameResult::Win(player) => {
+                if self.wins.len() <= player {
+                    self.wins.resize(player + 1, 0);
+                }
+                self.wins[player] += 1;
+            }
+            GameResult::Draw(_) => self.draws += 1,
+        }
+    }
+
+    /// Combines the statistics recorded by `other` into these statistics.
+    pub fn merge(&mut self, other: &PlayoutStats) {
+        self.games += other.games;
+        self.total_moves += other.total_moves;
+        self.draws += other.draws;
+        if self.wins.len() < other.wins.len() {
+            self.wins.resize(other.wins.len(), 0);
+        }
+        for (wins, other_wins) in self.wins.iter_mut().zip(&other.wins) {
+            *wins += other_wins;
+        }
+        for (&rounds, &count) in other.rounds.iter() {
+            *self.rounds.entry(rounds).or_default() += count;
+        }
+    }
+
+    /// Returns the mean number of moves played per game, or 0 if no games have been recorded.
+    pub fn mean_game_length(&self) -> f32 {
+        if self.games == 0 {
+            return 0.;
+        }
+        self.total_moves as f32 / self.games as f32
+    }
+
+    /// Returns the mean number of rounds played per game, or 0 if no games have been recorded.
+    pub fn mean_rounds(&self) -> f32 {
+        if self.games == 0 {
+            return 0.;
+        }
+        let total_rounds: usize = self.rounds.iter().map(|(r, c)| r * c).sum();
+        total_rounds as f32 / self.games as f32
+    }
+
+    /// Returns the fraction of recorded games won outright by the given player.
+    /// Shared victories are not counted as wins.
+    pub fn win_rate(&self, player: usize) -> f32 {
+        if self.games == 0 {
+            return 0.;
+        }
+        self.wins.get(player).copied().unwrap_or_default() as f32 / self.games as f32
+    }
+
+    /// Returns the fraction of recorded games which ended in a shared victory.
+    pub fn draw_rate(&self) -> f32 {
+        if self.games == 0 {
+            return 0.;
+        }
+        self.draws as f32 / self.games as f32
+    }
+
+    /// Returns the number of recorded games for each game length in rounds, ordered by length.
+    pub fn round_distribution(&self) -> &BTreeMap<usize, usize> {
+        &self.rounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_and_win_rates() {
+        let mut stats = PlayoutStats::new();
+        stats.record(GameResult::Win(0), 100, 5);
+        stats.record(GameResult::Win(1), 120, 6);
+        stats.record(GameResult::Win(0), 80, 5);
+        stats.record(GameResult::Draw(vec![0, 1]), 100, 6);
+
+        assert_eq!(*stats.games(), 4);
+        assert_eq!(stats.mean_game_length(), 100.);
+        assert_eq!(stats.mean_rounds(), 5.5);
+        assert_eq!(stats.win_rate(0), 0.5);
+        assert_eq!(stats.win_rate(1), 0.25);
+        assert_eq!(stats.win_rate(2), 0.);
+        assert_eq!(stats.draw_rate(), 0.25);
+        assert_eq!(stats.round_distribution()[&5], 2);
+    }
+
+    #[test]
+    fn merged_stats_match_recording_together() {
+        let mut first = PlayoutStats::new();
+        first.record(GameResult::Win(0), 90, 5);
+        let mut second = PlayoutStats::new();
+        second.record(GameResult::Win(2), 110, 7);
+
+        first.merge(&second);
+        assert_eq!(*first.games(), 2);
+        assert_eq!(first.mean_game_length(), 100.);
+        assert_eq!(first.win_rate(2), 0.5);
+    }
+}
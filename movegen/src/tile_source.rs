@@ -0,0 +1,34 @@
+/// The index of the centre tile space within a gamestate's bowls. This area is not technically a
+/// bowl in the original game, but for simplicity of storage and the move format, it is kept first
+/// among the bowls.
+pub const CENTRE_BOWL_IDX: usize = 0;
+
+/// The area a move takes its tiles from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileSource {
+    /// The centre area collects the tiles left over from each bowl and is never refilled.
+    /// The first player to take from the centre each round also takes the first player token.
+    Centre,
+    /// A bowl which is refilled at the beginning of each round.
+    /// The parameter `usize` represents the index of the bowl among the gamestate's bowls.
+    Bowl(usize),
+}
+
+impl TileSource {
+    /// Returns the tile source represented by the given bowl index.
+    pub fn from_bowl_index(bowl_idx: usize) -> Self {
+        if bowl_idx == CENTRE_BOWL_IDX {
+            TileSource::Centre
+        } else {
+            TileSource::Bowl(bowl_idx)
+        }
+    }
+
+    /// Returns the index of this tile source among the gamestate's bowls.
+    pub fn bowl_index(&self) -> usize {
+        match self {
+            TileSource::Centre => CENTRE_BOWL_IDX,
+            TileSource::Bowl(idx) => *idx,
+        }
+    }
+}
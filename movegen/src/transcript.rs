@@ -0,0 +1,183 @@
+use crate::{
+    Color, GameState, Move, Row, Tile,
+    board::{BonusEvent, ScoreReport},
+    game_move::{IllegalMoveError, MoveReport},
+    tile_source::TileSource,
+};
+
+impl GameState {
+    /// Replays a game of the given number of players, created with the given seed, and returns a
+    /// human-readable transcript of it, one line per event. Unlike a list of moves, the
+    /// transcript is meant to be read while learning the game.
+    ///
+    /// Each move is described along with the tiles it took and where they went. At the end of
+    /// each round, each player's line shows the change in their score, the tiles they placed and
+    /// the points those earned, any bonuses collected, and the points lost to penalties.
+    ///
+    /// The game is replayed under the default rules, starting a new round whenever the previous
+    /// one is over, as in [`GameState::replay_from_start`]. Any moves after the game is over are
+    /// ignored.
+    /// Will error with the first move which can't be played.
+    pub fn annotated_transcript(
+        players: usize,
+        seed: u64,
+        moves: &[Move],
+    ) -> Result<String, IllegalMoveError> {
+        let mut state = GameState::new_seeded(players, seed);
+        state.setup_next_round();
+
+        let mut lines = vec![format!("Round {}", state.round())];
+        for choice in moves {
+            let player = *state.active_player();
+            let had_token = state.first_token_owner().is_some();
+            let report = state
+                .make_move_detailed(choice)
+                .map_err(|reason| IllegalMoveError {
+                    choice: choice.clone(),
+                    reason,
+                })?;
+            let mut line = format!("  P{} {}", player, describe_move(choice, &report));
+            if !had_token && state.first_token_owner().is_some() {
+                line.push_str(", and the first player token");
+            }
+            lines.push(line);
+
+            if state.round_over() {
+                let round = *state.round();
+                let reports = state.setup_next_round();
+                lines.push(format!("End of round {}", round));
+                for (player, (report, board)) in reports.iter().zip(state.boards()).enumerate() {
+                    lines.push(format!(
+                        "  P{} {:+} -> {}: {}",
+                        player,
+                        report.score_change,
+                        board.get_score(),
+                        describe_scoring(report)
+                    ));
+                }
+                if state.is_game_over() {
+                    let winners: Vec<_> =
+                        state.winners().iter().map(|w| format!("P{}", w)).collect();
+                    lines.push(format!("Game over, won by {}", winners.join(", ")));
+                    break;
+                }
+                // The game can't continue if no tiles remain to be dealt
+                if state.round_over() {
+                    break;
+                }
+                lines.push(format!("Round {}", state.round()));
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Describes a move and where the tiles it took went, ex.
+/// `040102: took 2 yellow from bowl 4 into row 3, 1 to the floor, 2 to the centre`.
+/// Tiles left behind are only mentioned when taking from a bowl, since they stay in the centre.
+fn describe_move(choice: &Move, report: &MoveReport) -> String {
+    let source = match choice.source() {
+        TileSource::Centre => String::from("the centre"),
+        TileSource::Bowl(i) => format!("bowl {}", i),
+    };
+    let mut text = format!(
+        "{}: took {} {} from {}",
+        choice,
+        report.taken,
+        tile_name(choice.tile_type),
+        source
+    );
+    match choice.row {
+        Row::Wall(row) => text.push_str(&format!(" into row {}", row + 1)),
+        Row::Floor => text.push_str(" onto the floor"),
+    }
+    if report.to_floor > 0 && choice.row != Row::Floor {
+        text.push_str(&format!(", {} to the floor", report.to_floor));
+    }
+    if report.to_centre > 0 && choice.source() != TileSource::Centre {
+        text.push_str(&format!(", {} to the centre", report.to_centre));
+    }
+    text
+}
+
+/// Describes what a board scored at the end of a round, ex.
+/// `placed 2 tiles for 5 points, row 1 bonus, lost 2 to penalties`.
+fn describe_scoring(report: &ScoreReport) -> String {
+    let mut parts = Vec::new();
+    if !report.placed.is_empty() {
+        parts.push(format!(
+            "placed {} tile{} for {} point{}",
+            report.placed.len(),
+            if report.placed.len() == 1 { "" } else { "s" },
+            report.placement_points,
+            if report.placement_points == 1 {
+                ""
+            } else {
+                "s"
+            }
+        ));
+    }
+    for bonus in report.bonuses.iter() {
+        parts.push(match bonus {
+            BonusEvent::Row(row) => format!("row {} bonus", row + 1),
+            BonusEvent::Column(col) => format!("column {} bonus", col + 1),
+            BonusEvent::Color(tile_type) => format!("{} bonus", tile_name(*tile_type)),
+        });
+    }
+    if report.penalty_points > 0 {
+        parts.push(format!("lost {} to penalties", report.penalty_points));
+    }
+    if parts.is_empty() {
+        return String::from("nothing scored");
+    }
+    parts.join(", ")
+}
+
+/// Returns the colour name of the given tile type, or its index if it isn't a standard tile type.
+fn tile_name(tile_type: Tile) -> String {
+    Color::from_tile(tile_type)
+        .map(|c| c.name().to_string())
+        .unwrap_or_else(|| format!("tile {}", tile_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcripts_show_the_score_change_of_each_round() {
+        // Each player fills their top row with their first move, and sends everything else to the
+        // floor, so both place a single tile at the end of the round
+        let mut state = GameState::new_seeded(2, 16);
+        state.setup_next_round();
+        let mut moves = Vec::new();
+        while !state.round_over() {
+            let valid = state.get_valid_moves();
+            let choice = valid
+                .iter()
+                .find(|m| m.row == Row::Wall(0))
+                .or_else(|| valid.iter().find(|m| m.row == Row::Floor))
+                .unwrap()
+                .clone();
+            state.make_move(&choice).unwrap();
+            moves.push(choice);
+        }
+        let reports = state.clone().setup_next_round();
+
+        let transcript = GameState::annotated_transcript(2, 16, &moves).unwrap();
+        assert!(transcript.starts_with("Round 1\n"));
+        assert!(transcript.contains(&format!("  P0 {}: took", moves[0])));
+        assert!(transcript.contains("End of round 1"));
+        for (player, report) in reports.iter().enumerate() {
+            assert_eq!(report.placement_points, 1);
+            let line = format!(
+                "  P{} {:+} -> {}: placed 1 tile for 1 point",
+                player,
+                report.score_change,
+                report.score_change.max(0)
+            );
+            assert!(transcript.contains(&line), "{}", transcript);
+        }
+        assert!(transcript.ends_with("Round 2"));
+    }
+}